@@ -9,8 +9,8 @@ use criterion::{criterion_group, criterion_main, Criterion};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use union_find::{
-    QuickFindUf, QuickUnionUf, Union, UnionByRank, UnionByRankSize, UnionBySize, UnionBySizeRank,
-    UnionFind,
+    CompactQuickUnionUf, QuickFindUf, QuickUnionUf, Union, UnionByRank, UnionByRankSize,
+    UnionBySize, UnionBySizeRank, UnionFind,
 };
 
 struct Cache<'a, T> {
@@ -297,6 +297,7 @@ fn bench(c: &mut Criterion) {
     for input in &[&tiny, &medium, &large] {
         input.bench_full::<QuickUnionUf<UnionBySize>, _>(c, "quick_union");
         input.bench_full::<QuickFindUf<UnionBySize>, _>(c, "quick_find");
+        input.bench_full::<CompactQuickUnionUf, _>(c, "compact_quick_union");
     }
 
     {
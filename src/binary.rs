@@ -0,0 +1,227 @@
+// Copyright 2016 union-find-rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::util;
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+
+pub(crate) const MAGIC: [u8; 8] = *b"UNIONFND";
+pub(crate) const VERSION: u32 = 1;
+pub(crate) const HEADER_SIZE: usize = 40;
+
+/// The value that is stored in a fixed number of bytes in the binary format
+/// of `QuickUnionUf`.
+pub trait Codec: Sized {
+    /// The number of bytes of an encoded value.
+    const SIZE: usize;
+
+    /// Writes `self` into `buf`, which is `SIZE` bytes long.
+    fn encode(&self, buf: &mut [u8]);
+
+    /// Reads a value from `buf`, which is `SIZE` bytes long.
+    ///
+    /// Returns `None` if `buf` does not contain a valid value.
+    fn decode(buf: &[u8]) -> Option<Self>;
+}
+
+pub(crate) fn encode_size(size: usize, buf: &mut [u8]) {
+    buf.copy_from_slice(&(size as u64).to_le_bytes());
+}
+
+pub(crate) fn decode_size(buf: &[u8]) -> Option<usize> {
+    usize::try_from(u64::from_le_bytes(buf.try_into().unwrap())).ok()
+}
+
+pub(crate) fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// The header of the binary format.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Header {
+    pub(crate) len: usize,
+    pub(crate) set_count: usize,
+}
+
+impl Header {
+    pub(crate) fn write_to<W: Write>(&self, payload_size: usize, mut writer: W) -> io::Result<()> {
+        let mut buf = [0; HEADER_SIZE];
+        buf[0..8].copy_from_slice(&MAGIC);
+        buf[8..12].copy_from_slice(&VERSION.to_le_bytes());
+        buf[12..16].copy_from_slice(&0u32.to_le_bytes());
+        buf[16..24].copy_from_slice(&(self.len as u64).to_le_bytes());
+        buf[24..32].copy_from_slice(&(self.set_count as u64).to_le_bytes());
+        buf[32..40].copy_from_slice(&(payload_size as u64).to_le_bytes());
+        writer.write_all(&buf)
+    }
+
+    pub(crate) fn read_from<R: Read>(payload_size: usize, mut reader: R) -> io::Result<Header> {
+        let mut buf = [0; HEADER_SIZE];
+        reader.read_exact(&mut buf)?;
+        Header::parse(payload_size, &buf)
+    }
+
+    fn parse(payload_size: usize, buf: &[u8; HEADER_SIZE]) -> io::Result<Header> {
+        let u32_at = |i: usize| u32::from_le_bytes(buf[i..i + 4].try_into().unwrap());
+        let usize_at = |i: usize| decode_size(&buf[i..i + 8]);
+
+        if buf[0..8] != MAGIC {
+            return Err(invalid_data("not a union-find snapshot"));
+        }
+        if u32_at(8) != VERSION {
+            return Err(invalid_data("unsupported snapshot version"));
+        }
+        if u32_at(12) != 0 {
+            return Err(invalid_data("unknown snapshot flags"));
+        }
+        if usize_at(32) != Some(payload_size) {
+            return Err(invalid_data("payload size mismatch"));
+        }
+        let len = usize_at(16).ok_or_else(|| invalid_data("snapshot too large"))?;
+        let set_count = usize_at(24)
+            .filter(|&n| n <= len)
+            .ok_or_else(|| invalid_data("invalid set count"))?;
+        Ok(Header { len, set_count })
+    }
+}
+
+/// Read-only view of a `QuickUnionUf` snapshot in its binary format.
+///
+/// `find` and `get` read the parent links and the values directly from the
+/// bytes, so a snapshot can be queried without copying it into memory, for
+/// example from a memory-mapped file. The snapshot is validated once in
+/// `from_bytes`.
+///
+/// ```
+/// use union_find::{QuickUnionUf, QuickUnionView, UnionBySize, UnionFind};
+///
+/// let mut uf = QuickUnionUf::<UnionBySize>::new(4);
+/// uf.union(0, 1);
+/// uf.union(1, 2);
+///
+/// let mut bytes = vec![];
+/// uf.write_to(&mut bytes).unwrap();
+///
+/// let view = QuickUnionView::<UnionBySize>::from_bytes(&bytes).unwrap();
+/// assert!(view.connected(0, 2));
+/// assert!(!view.connected(0, 3));
+/// assert_eq!(view.get(2).size(), 3);
+/// ```
+#[derive(Debug)]
+pub struct QuickUnionView<'a, V> {
+    parents: &'a [u8],
+    payload: &'a [u8],
+    set_count: usize,
+    _marker: PhantomData<fn() -> V>,
+}
+
+impl<V> Clone for QuickUnionView<'_, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for QuickUnionView<'_, V> {}
+
+impl<'a, V: Codec> QuickUnionView<'a, V> {
+    /// Creates a view of the snapshot written by `QuickUnionUf::write_to`.
+    ///
+    /// Returns an error of kind `InvalidData` if the bytes are not a valid
+    /// snapshot. This takes O(n) time to check the parent links.
+    pub fn from_bytes(bytes: &'a [u8]) -> io::Result<QuickUnionView<'a, V>> {
+        let header_bytes = bytes
+            .get(..HEADER_SIZE)
+            .ok_or_else(|| invalid_data("snapshot too short"))?;
+        let header = Header::parse(V::SIZE, header_bytes.try_into().unwrap())?;
+
+        let parents_size = header.len.checked_mul(8);
+        let payload_size = header.len.checked_mul(V::SIZE);
+        let total = parents_size
+            .zip(payload_size)
+            .and_then(|(p, v)| HEADER_SIZE.checked_add(p)?.checked_add(v));
+        if total != Some(bytes.len()) {
+            return Err(invalid_data("snapshot size mismatch"));
+        }
+        let (parents, rest) = bytes[HEADER_SIZE..].split_at(header.len * 8);
+        let payload = &rest[..header.len * V::SIZE];
+
+        let view = QuickUnionView {
+            parents,
+            payload,
+            set_count: header.set_count,
+            _marker: PhantomData,
+        };
+        view.validate()?;
+        Ok(view)
+    }
+
+    /// Returns the size of `self`.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.parents.len() / 8
+    }
+
+    /// Returns the number of disjoint sets in `self`.
+    #[inline]
+    pub fn set_count(&self) -> usize {
+        self.set_count
+    }
+
+    /// Returns the identifier of the set that the key belongs to.
+    #[inline]
+    pub fn find(&self, key: usize) -> usize {
+        let mut k = key;
+        let mut p = self.parent(k);
+        while p != k {
+            k = p;
+            p = self.parent(k);
+        }
+        k
+    }
+
+    /// Returns `true` if given keys belong to the same set.
+    #[inline]
+    pub fn connected(&self, key0: usize, key1: usize) -> bool {
+        self.find(key0) == self.find(key1)
+    }
+
+    /// Returns the value of the set that the key belongs to.
+    #[inline]
+    pub fn get(&self, key: usize) -> V {
+        V::decode(self.payload_of(self.find(key))).unwrap()
+    }
+
+    #[inline]
+    fn parent(&self, key: usize) -> usize {
+        // Every link has been checked to be in range by `validate`.
+        decode_size(&self.parents[key * 8..key * 8 + 8]).unwrap()
+    }
+
+    #[inline]
+    fn payload_of(&self, key: usize) -> &'a [u8] {
+        &self.payload[key * V::SIZE..(key + 1) * V::SIZE]
+    }
+
+    fn validate(&self) -> io::Result<()> {
+        let len = self.size();
+        let parent = |k: usize| decode_size(&self.parents[k * 8..k * 8 + 8]).unwrap_or(usize::MAX);
+        util::check_forest(len, parent).map_err(invalid_data)?;
+
+        let mut set_count = 0;
+        for key in (0..len).filter(|&k| parent(k) == k) {
+            if V::decode(self.payload_of(key)).is_none() {
+                return Err(invalid_data("invalid payload"));
+            }
+            set_count += 1;
+        }
+        if set_count != self.set_count {
+            return Err(invalid_data("set count mismatch"));
+        }
+        Ok(())
+    }
+}
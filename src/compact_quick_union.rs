@@ -0,0 +1,226 @@
+// Copyright 2016 union-find-rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::{Union, UnionFind, UnionOutcome, UnionResult};
+use std::iter::FromIterator;
+
+/// The maximum number of elements.
+const MAX_SIZE: usize = i32::MAX as usize;
+
+/// Union-Find implementation with quick union operation, which packs the
+/// links and the sizes of the sets into 32 bits per element.
+///
+/// Each key has a single `i32` link. For a non-root key it is the parent key,
+/// and for a root key it is the size of the set, negated. The value of a set
+/// is the `CompactSize` in the link of its root, so `self` uses exactly 4
+/// bytes per element, and the sets are always joined by size.
+///
+/// The number of elements is limited to `2^31 - 1`.
+///
+/// ```
+/// use union_find::{CompactQuickUnionUf, UnionFind};
+///
+/// let mut uf = CompactQuickUnionUf::new(4);
+/// assert!(uf.union(0, 1));
+/// assert!(uf.union(1, 2));
+/// assert_eq!(uf.get(2).size(), 3);
+/// assert_eq!(std::mem::size_of_val(uf.get(3)), 4);
+/// ```
+#[derive(Debug)]
+pub struct CompactQuickUnionUf {
+    // The links of non-root keys are `CompactSize`s too, so that `get` can
+    // return a reference to the link of a root.
+    link: Vec<CompactSize>,
+    set_count: usize,
+}
+
+impl Clone for CompactQuickUnionUf {
+    #[inline]
+    fn clone(&self) -> CompactQuickUnionUf {
+        CompactQuickUnionUf {
+            link: self.link.clone(),
+            set_count: self.set_count,
+        }
+    }
+
+    #[inline]
+    fn clone_from(&mut self, other: &CompactQuickUnionUf) {
+        self.link.clone_from(&other.link);
+        self.set_count = other.set_count;
+    }
+}
+
+impl UnionFind<CompactSize> for CompactQuickUnionUf {
+    #[inline]
+    fn size(&self) -> usize {
+        self.link.len()
+    }
+
+    #[inline]
+    fn set_count(&self) -> usize {
+        self.set_count
+    }
+
+    #[inline]
+    fn insert(&mut self, data: CompactSize) -> usize {
+        let key = self.link.len();
+        assert!(key < MAX_SIZE, "too many elements");
+        self.link.push(data);
+        self.set_count += 1;
+        key
+    }
+
+    #[inline]
+    fn union(&mut self, key0: usize, key1: usize) -> bool {
+        self.union_detailed(key0, key1).is_merged()
+    }
+
+    #[inline]
+    fn union_detailed(&mut self, key0: usize, key1: usize) -> UnionOutcome<usize> {
+        let k0 = self.find(key0);
+        let k1 = self.find(key1);
+        if k0 == k1 {
+            return UnionOutcome::AlreadyJoined { root: k0 };
+        }
+
+        let (parent, child, val) = match Union::union(self.link[k0], self.link[k1]) {
+            UnionResult::Left(val) => (k0, k1, val),
+            UnionResult::Right(val) => (k1, k0, val),
+        };
+        self.link[parent] = val;
+        self.link[child] = CompactSize {
+            link: parent as i32,
+        };
+
+        self.set_count -= 1;
+
+        UnionOutcome::Merged {
+            root: parent,
+            absorbed: child,
+        }
+    }
+
+    #[inline]
+    fn find(&mut self, key: usize) -> usize {
+        let mut k = key;
+        loop {
+            let p = self.link[k].link;
+            if p < 0 {
+                return k;
+            }
+            let pp = self.link[p as usize].link;
+            if pp < 0 {
+                return p as usize;
+            }
+            self.link[k].link = pp;
+            k = p as usize;
+        }
+    }
+
+    #[inline]
+    fn get(&mut self, key: usize) -> &CompactSize {
+        let root_key = self.find(key);
+        &self.link[root_key]
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: usize) -> &mut CompactSize {
+        let root_key = self.find(key);
+        &mut self.link[root_key]
+    }
+
+    #[inline]
+    fn find_immutable(&self, key: usize) -> usize {
+        let mut k = key;
+        loop {
+            let p = self.link[k].link;
+            if p < 0 {
+                return k;
+            }
+            k = p as usize;
+        }
+    }
+
+    #[inline]
+    fn get_immutable(&self, key: usize) -> &CompactSize {
+        let root_key = self.find_immutable(key);
+        &self.link[root_key]
+    }
+}
+
+impl FromIterator<CompactSize> for CompactQuickUnionUf {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = CompactSize>>(iterator: T) -> CompactQuickUnionUf {
+        let mut uf = CompactQuickUnionUf {
+            link: vec![],
+            set_count: 0,
+        };
+        uf.extend(iterator);
+        uf
+    }
+}
+
+impl Extend<CompactSize> for CompactQuickUnionUf {
+    #[inline]
+    fn extend<T>(&mut self, iterable: T)
+    where
+        T: IntoIterator<Item = CompactSize>,
+    {
+        let iter = iterable.into_iter();
+        let (lower, _) = iter.size_hint();
+        self.link.reserve(lower);
+        for data in iter {
+            let _ = self.insert(data);
+        }
+    }
+}
+
+/// The value of a set in `CompactQuickUnionUf`.
+///
+/// It is the negated size of the set, which is stored in the link of the
+/// root. A smaller sized set will be the children of a larger sized set.
+///
+/// A `CompactSize` can only be created by `Default` and `Union::union`, so it
+/// is always negative, and a value written by `insert` or `get_mut` is never
+/// read as a parent link. `Union::union` panics if the joined size does not
+/// fit in the link.
+#[derive(Copy, Clone, Debug)]
+pub struct CompactSize {
+    link: i32,
+}
+
+impl Union for CompactSize {
+    #[inline]
+    fn union(left: CompactSize, right: CompactSize) -> UnionResult<CompactSize> {
+        let result = CompactSize {
+            link: left
+                .link
+                .checked_add(right.link)
+                .expect("too many elements"),
+        };
+        if left.size() >= right.size() {
+            UnionResult::Left(result)
+        } else {
+            UnionResult::Right(result)
+        }
+    }
+}
+
+impl Default for CompactSize {
+    #[inline]
+    fn default() -> CompactSize {
+        CompactSize { link: -1 }
+    }
+}
+
+impl CompactSize {
+    /// Returns the size of the set.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.link.unsigned_abs() as usize
+    }
+}
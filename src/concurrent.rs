@@ -0,0 +1,167 @@
+// Copyright 2016 union-find-rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::{Idx, Union, UnionFind};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Lock-free Union-Find implementation that can be shared between threads.
+///
+/// All operations take `&self`, so `union` and `find` can be called from many
+/// threads at once. Roots are linked with compare-and-swap, always making the
+/// root with the larger key a child of the root with the smaller key, and
+/// `find` halves paths with compare-and-swap as well.
+///
+/// There are no values attached to the sets. After the concurrent phase,
+/// the partition can be moved into a regular union-find structure with
+/// `into_union_find`.
+///
+/// ```
+/// use std::thread;
+/// use union_find::{ConcurrentUf, QuickUnionUf, UnionBySize, UnionFind};
+///
+/// let uf = ConcurrentUf::new(100);
+/// thread::scope(|s| {
+///     for t in 0..4 {
+///         let uf = &uf;
+///         s.spawn(move || {
+///             for i in (t..99).step_by(4) {
+///                 uf.union(i, i + 1);
+///             }
+///         });
+///     }
+/// });
+/// assert!(uf.connected(0, 99));
+///
+/// let mut uf: QuickUnionUf<UnionBySize> = uf.into_union_find();
+/// assert_eq!(uf.get(0).size(), 100);
+/// ```
+#[derive(Debug)]
+pub struct ConcurrentUf {
+    link_parent: Vec<AtomicUsize>,
+}
+
+impl ConcurrentUf {
+    /// Creates `ConcurrentUf` struct that contains `len` sets.
+    #[inline]
+    pub fn new(len: usize) -> ConcurrentUf {
+        ConcurrentUf {
+            link_parent: (0..len).map(AtomicUsize::new).collect(),
+        }
+    }
+
+    /// Returns the size of `self`.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.link_parent.len()
+    }
+
+    /// Join two sets that contains given keys (union operation).
+    ///
+    /// Returns `true` if these keys are belonged to different sets. When
+    /// several threads join the same two sets at once, exactly one of them
+    /// returns `true`.
+    #[inline]
+    pub fn union(&self, key0: usize, key1: usize) -> bool {
+        let mut k0 = key0;
+        let mut k1 = key1;
+        loop {
+            k0 = self.find(k0);
+            k1 = self.find(k1);
+            if k0 == k1 {
+                return false;
+            }
+
+            // Linking in the order of the keys never creates a cycle.
+            let (child, parent) = if k0 > k1 { (k0, k1) } else { (k1, k0) };
+            if self.link_parent[child]
+                .compare_exchange(child, parent, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                return true;
+            }
+            // `child` has been linked by another thread, so retry from it.
+        }
+    }
+
+    /// Returns the identifier of the set that the key belongs to.
+    ///
+    /// The identifier may be outdated as soon as it is returned if other
+    /// threads call `union` at the same time.
+    #[inline]
+    pub fn find(&self, key: usize) -> usize {
+        let mut k = key;
+        loop {
+            let p = self.link_parent[k].load(Ordering::Acquire);
+            if p == k {
+                return k;
+            }
+            let pp = self.link_parent[p].load(Ordering::Acquire);
+            if p != pp {
+                // Failure means that another thread has already updated the
+                // link, which is fine for path halving.
+                let _ = self.link_parent[k].compare_exchange_weak(
+                    p,
+                    pp,
+                    Ordering::AcqRel,
+                    Ordering::Relaxed,
+                );
+            }
+            k = pp;
+        }
+    }
+
+    /// Returns `true` if given keys belong to the same set.
+    #[inline]
+    pub fn connected(&self, key0: usize, key1: usize) -> bool {
+        let mut k0 = key0;
+        let mut k1 = key1;
+        loop {
+            k0 = self.find(k0);
+            k1 = self.find(k1);
+            if k0 == k1 {
+                return true;
+            }
+            // If `k0` is still a root, the keys were in different sets at the
+            // moment `k1` was found.
+            if self.link_parent[k0].load(Ordering::Acquire) == k0 {
+                return false;
+            }
+        }
+    }
+
+    /// Joins the sets of `uf` so that it contains the partition of `self`.
+    ///
+    /// The sets that are already joined in `uf` are kept joined.
+    pub(crate) fn apply_to<U, V, K>(&self, uf: &mut U)
+    where
+        U: UnionFind<V, K>,
+        V: Union,
+        K: Idx,
+    {
+        for key in 0..self.size() {
+            let root = self.find(key);
+            if root != key {
+                let _ = uf.union(K::from_index(root), K::from_index(key));
+            }
+        }
+    }
+
+    /// Converts `self` into a regular union-find structure that has the same
+    /// partition.
+    ///
+    /// The values of the sets are computed by joining default values.
+    #[inline]
+    pub fn into_union_find<U, V>(self) -> U
+    where
+        U: UnionFind<V>,
+        V: Union + Default,
+    {
+        let mut uf = U::new(self.size());
+        self.apply_to(&mut uf);
+        uf
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright 2016 union-find-rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::error::Error;
+use std::fmt;
+
+/// The error returned by the fallible operations of `UnionFind`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UfError {
+    /// The key is not less than the size of the union-find.
+    KeyOutOfRange {
+        /// The index of the key.
+        key: usize,
+        /// The size of the union-find.
+        size: usize,
+    },
+    /// The root of a set has no value, i.e. the union-find is corrupted.
+    MissingPayload {
+        /// The index of the root.
+        key: usize,
+    },
+}
+
+impl fmt::Display for UfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            UfError::KeyOutOfRange { key, size } => {
+                write!(f, "key {key} is out of range for size {size}")
+            }
+            UfError::MissingPayload { key } => write!(f, "root {key} has no value"),
+        }
+    }
+}
+
+impl Error for UfError {}
@@ -0,0 +1,76 @@
+// Copyright 2016 union-find-rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::convert::TryFrom;
+use std::fmt::Debug;
+
+/// The type that can be used as a key of the union-find structures.
+///
+/// Keys are assigned sequentially from `0` by the structures, so an `Idx`
+/// only needs to convert from and to `usize`. Implementing this for a
+/// newtype prevents keys of different structures from being mixed up, and
+/// implementing it over `u32` halves the memory used for the links.
+///
+/// ```
+/// use union_find::{Idx, QuickUnionUf, UnionBySize, UnionFind};
+///
+/// #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// struct NodeId(u32);
+///
+/// impl Idx for NodeId {
+///     fn from_index(index: usize) -> NodeId {
+///         NodeId(Idx::from_index(index))
+///     }
+///
+///     fn index(self) -> usize {
+///         self.0.index()
+///     }
+/// }
+///
+/// let mut uf = QuickUnionUf::<UnionBySize, NodeId>::new(2);
+/// let a = NodeId(0);
+/// let b = NodeId(1);
+/// let c = uf.insert(UnionBySize::default());
+/// assert_eq!(c, NodeId(2));
+///
+/// assert!(uf.union(a, c));
+/// assert_eq!(uf.find(c), a);
+/// assert_ne!(uf.find(b), a);
+/// ```
+pub trait Idx: Copy + Eq + Debug {
+    /// Creates a key from the index.
+    ///
+    /// This panics if the index is not representable by `Self`.
+    fn from_index(index: usize) -> Self;
+
+    /// Returns the index of the key.
+    fn index(self) -> usize;
+}
+
+impl Idx for usize {
+    #[inline]
+    fn from_index(index: usize) -> usize {
+        index
+    }
+
+    #[inline]
+    fn index(self) -> usize {
+        self
+    }
+}
+
+impl Idx for u32 {
+    #[inline]
+    fn from_index(index: usize) -> u32 {
+        u32::try_from(index).expect("index out of range for `u32` key")
+    }
+
+    #[inline]
+    fn index(self) -> usize {
+        self as usize
+    }
+}
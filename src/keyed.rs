@@ -0,0 +1,233 @@
+// Copyright 2016 union-find-rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::{Union, UnionFind};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::iter;
+use std::marker::PhantomData;
+
+/// Union-Find wrapper that accepts arbitrary hashable elements as keys.
+///
+/// Each element is mapped to a key of the underlying `UnionFind` structure
+/// `U` the first time it is seen. Methods that take an element which has not
+/// been seen yet insert it as a new set with the default value.
+///
+/// ```
+/// use union_find::{KeyedUf, QuickUnionUf, UnionBySize};
+///
+/// let mut uf = KeyedUf::<&str, UnionBySize, QuickUnionUf<UnionBySize>>::new();
+/// assert!(uf.union(&"apple", &"banana"));
+/// assert!(uf.union(&"banana", &"cherry"));
+/// assert!(!uf.union(&"cherry", &"apple"));
+///
+/// let root = *uf.find(&"cherry");
+/// assert_eq!(&root, uf.find(&"apple"));
+/// assert_eq!(uf.get(&"banana").size(), 3);
+/// assert_eq!(uf.get(&"durian").size(), 1);
+/// ```
+pub struct KeyedUf<K, V, U> {
+    uf: U,
+    keys: Vec<K>,
+    indices: HashMap<K, usize>,
+    _marker: PhantomData<fn() -> V>,
+}
+
+impl<K, V, U> fmt::Debug for KeyedUf<K, V, U>
+where
+    K: fmt::Debug,
+    U: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedUf")
+            .field("uf", &self.uf)
+            .field("keys", &self.keys)
+            .finish()
+    }
+}
+
+impl<K, V, U> Clone for KeyedUf<K, V, U>
+where
+    K: Clone,
+    U: Clone,
+{
+    #[inline]
+    fn clone(&self) -> KeyedUf<K, V, U> {
+        KeyedUf {
+            uf: self.uf.clone(),
+            keys: self.keys.clone(),
+            indices: self.indices.clone(),
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn clone_from(&mut self, other: &KeyedUf<K, V, U>) {
+        self.uf.clone_from(&other.uf);
+        self.keys.clone_from(&other.keys);
+        self.indices.clone_from(&other.indices);
+    }
+}
+
+impl<K, V, U> Default for KeyedUf<K, V, U>
+where
+    K: Hash + Eq + Clone,
+    V: Union,
+    U: UnionFind<V>,
+{
+    #[inline]
+    fn default() -> KeyedUf<K, V, U> {
+        KeyedUf::new()
+    }
+}
+
+impl<K, V, U> KeyedUf<K, V, U>
+where
+    K: Hash + Eq + Clone,
+    V: Union,
+    U: UnionFind<V>,
+{
+    /// Creates empty `KeyedUf` struct.
+    #[inline]
+    pub fn new() -> KeyedUf<K, V, U> {
+        KeyedUf {
+            uf: U::from_iter(iter::empty()),
+            keys: vec![],
+            indices: HashMap::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements in `self`.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns the number of disjoint sets in `self`.
+    #[inline]
+    pub fn set_count(&self) -> usize {
+        self.uf.set_count()
+    }
+
+    /// Returns `true` if the element has been inserted into `self`.
+    #[inline]
+    pub fn contains(&self, elem: &K) -> bool {
+        self.indices.contains_key(elem)
+    }
+
+    /// Returns the key of the element in the underlying structure.
+    #[inline]
+    pub fn index_of(&self, elem: &K) -> Option<usize> {
+        self.indices.get(elem).copied()
+    }
+
+    /// Returns the element that corresponds to the key of the underlying
+    /// structure.
+    #[inline]
+    pub fn elem_of(&self, index: usize) -> &K {
+        &self.keys[index]
+    }
+
+    /// Inserts a new set that contains only the element.
+    ///
+    /// Returns `false` and discards `data` if the element has already been
+    /// inserted.
+    #[inline]
+    pub fn insert(&mut self, elem: K, data: V) -> bool {
+        if self.indices.contains_key(&elem) {
+            return false;
+        }
+        let index = self.uf.insert(data);
+        debug_assert_eq!(index, self.keys.len());
+        self.keys.push(elem.clone());
+        let _ = self.indices.insert(elem, index);
+        true
+    }
+
+    /// Join two sets that contains given elements (union operation).
+    ///
+    /// Returns `true` if these elements are belonged to different sets.
+    #[inline]
+    pub fn union(&mut self, elem0: &K, elem1: &K) -> bool
+    where
+        V: Default,
+    {
+        let k0 = self.index_or_insert(elem0);
+        let k1 = self.index_or_insert(elem1);
+        self.uf.union(k0, k1)
+    }
+
+    /// Returns `true` if given elements belong to the same set.
+    ///
+    /// This does not insert elements that have not been seen yet.
+    #[inline]
+    pub fn connected(&mut self, elem0: &K, elem1: &K) -> bool {
+        match (self.index_of(elem0), self.index_of(elem1)) {
+            (Some(k0), Some(k1)) => self.uf.connected(k0, k1),
+            _ => elem0 == elem1,
+        }
+    }
+
+    /// Returns the representative element of the set that the element
+    /// belongs to.
+    #[inline]
+    pub fn find(&mut self, elem: &K) -> &K
+    where
+        V: Default,
+    {
+        let key = self.index_or_insert(elem);
+        let root = self.uf.find(key);
+        &self.keys[root]
+    }
+
+    /// Returns the reference to the value of the set that the element
+    /// belongs to.
+    #[inline]
+    pub fn get(&mut self, elem: &K) -> &V
+    where
+        V: Default,
+    {
+        let key = self.index_or_insert(elem);
+        self.uf.get(key)
+    }
+
+    /// Returns the mutable reference to the value of the set that the
+    /// element belongs to.
+    #[inline]
+    pub fn get_mut(&mut self, elem: &K) -> &mut V
+    where
+        V: Default,
+    {
+        let key = self.index_or_insert(elem);
+        self.uf.get_mut(key)
+    }
+
+    /// Returns the reference to the underlying structure.
+    #[inline]
+    pub fn inner(&self) -> &U {
+        &self.uf
+    }
+
+    /// Unwraps the underlying structure.
+    #[inline]
+    pub fn into_inner(self) -> U {
+        self.uf
+    }
+
+    fn index_or_insert(&mut self, elem: &K) -> usize
+    where
+        V: Default,
+    {
+        if let Some(&index) = self.indices.get(elem) {
+            return index;
+        }
+        let _ = self.insert(elem.clone(), V::default());
+        self.keys.len() - 1
+    }
+}
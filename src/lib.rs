@@ -10,11 +10,27 @@
 //! This crate implements 2 variations of the union find datastructure.
 //! `QuickFindUf` implements an always O(1) `find` while `QuickUnionUf` has a
 //! `union` operation that is always O(1).
+//! `QuickUnionRingUf` extends `QuickUnionUf` with a circular list of the
+//! members of each set, so that the members can be enumerated like with
+//! `QuickFindUf`, and `CompactQuickUnionUf` is a variant of `QuickUnionUf`
+//! that packs the links and the sizes of the sets into 4 bytes per element.
+//!
+//! `RollbackUf` can undo `union` operations back to a checkpoint, and
+//! `PersistentUf` keeps every old version of itself alive.
+//! `ConcurrentUf` supports `union` and `find` from many threads at once.
+//! `WeightedUf` keeps the differences between the potentials of the keys in
+//! each set, and rejects constraints that contradict them. `BipartiteUf` is
+//! its specialization for checking that a graph is bipartite.
+//! `QuickUnionUf` can be saved in a compact binary format, which
+//! `QuickUnionView` can query without loading it.
 //!
 //! There is also the option whether the union operation joins the underlying
 //! tree structure by size or by rank via the `UnionBySize`, `UnionByRank`,
 //! `UnionByRankSize` and `UnionBySizeRank` structs that need to be passed
-//! to the Union Find datastructure.
+//! to the Union Find datastructure. `UnionByIndex` and `UnionByRandom` join
+//! the trees by the keys and by random priorities. Any of them can be paired
+//! with user data that implements `Merge`, as in
+//! `QuickUnionUf<(UnionBySize, MyData)>`.
 //!
 //! ```
 //! use union_find::{UnionFind, UnionBySize, QuickUnionUf};
@@ -49,15 +65,187 @@
 #![warn(unused_results)]
 
 mod traits;
-pub use crate::traits::{Union, UnionFind, UnionResult};
+pub use crate::traits::{
+    Detach, Merge, Split, Union, UnionFind, UnionOutcome, UnionResult, Unmerge,
+};
+
+mod idx;
+pub use crate::idx::Idx;
+
+mod error;
+pub use crate::error::UfError;
+
+mod merge;
+pub use crate::merge::{Max, Min, Or, Sum};
 
 mod union;
-pub use crate::union::{UnionByRank, UnionByRankSize, UnionBySize, UnionBySizeRank};
+pub use crate::union::{
+    UnionByIndex, UnionByRandom, UnionByRank, UnionByRankSize, UnionBySize, UnionBySizeRank,
+};
 
 mod quick_union;
 pub use crate::quick_union::QuickUnionUf;
+mod quick_union_ring;
+pub use crate::quick_union_ring::QuickUnionRingUf;
+mod compact_quick_union;
+pub use crate::compact_quick_union::{CompactQuickUnionUf, CompactSize};
 mod quick_find;
-pub use crate::quick_find::QuickFindUf;
+pub use crate::quick_find::{Members, QuickFindUf};
+
+mod rollback;
+pub use crate::rollback::{Checkpoint, RollbackUf};
+mod persistent;
+pub use crate::persistent::PersistentUf;
+mod concurrent;
+pub use crate::concurrent::ConcurrentUf;
+mod weighted;
+pub use crate::weighted::{BipartiteUf, Conflict, Group, Parity, WeightedUf};
+
+mod binary;
+pub use crate::binary::{Codec, QuickUnionView};
+
+mod keyed;
+pub use crate::keyed::KeyedUf;
+
+/// Derives `Union` for a struct whose fields are merged one by one.
+///
+/// Exactly one field must be marked with `#[union(link)]`. Its type
+/// implements `Union` and decides how the sets are linked. Every other field
+/// is merged by `Merge::merge`, for example with `Sum`, `Min`, `Max` or `Or`,
+/// or by the function given with `#[union(with = path)]`, which takes the
+/// values of the left and the right sets and returns the merged value.
+///
+/// ```
+/// use union_find::{Max, QuickUnionUf, Sum, Union, UnionBySize, UnionFind};
+///
+/// fn concat(mut left: Vec<u32>, right: Vec<u32>) -> Vec<u32> {
+///     left.extend(right);
+///     left
+/// }
+///
+/// #[derive(Union)]
+/// struct Stats {
+///     #[union(link)]
+///     size: UnionBySize,
+///     total: Sum<u32>,
+///     peak: Max<u32>,
+///     #[union(with = concat)]
+///     items: Vec<u32>,
+/// }
+///
+/// let mut uf: QuickUnionUf<Stats> = [3, 1, 4]
+///     .into_iter()
+///     .map(|n| Stats {
+///         size: UnionBySize::default(),
+///         total: Sum(n),
+///         peak: Max(n),
+///         items: vec![n],
+///     })
+///     .collect();
+/// uf.union(0, 1);
+/// uf.union(1, 2);
+///
+/// let stats = uf.get(0);
+/// assert_eq!(stats.size.size(), 3);
+/// assert_eq!(stats.total, Sum(8));
+/// assert_eq!(stats.peak, Max(4));
+/// assert_eq!(stats.items, [3, 1, 4]);
+/// ```
+#[cfg(feature = "derive")]
+pub use union_find_derive::Union;
+
+/// Derives `Detach` for a struct that derives `Union`.
+///
+/// The link field is detached by `Detach::detach`, and every other field is
+/// detached by `Unmerge::unmerge`, or by the function given with
+/// `#[union(detach = path)]`, which takes the value of the set and a
+/// reference to the value of the removed element. A field merged with
+/// `#[union(with = path)]` needs `detach = path` as well.
+///
+/// The derive does not compile if a field cannot be detached, for example
+/// with `Min`, `Max` or `Or`, since their values cannot be recomputed
+/// without the removed element.
+///
+/// ```
+/// use std::collections::BTreeSet;
+/// use union_find::{Detach, QuickFindUf, Sum, Union, UnionBySize, UnionFind};
+///
+/// fn join(mut left: BTreeSet<u32>, right: BTreeSet<u32>) -> BTreeSet<u32> {
+///     left.extend(right);
+///     left
+/// }
+///
+/// fn remove(set: BTreeSet<u32>, removed: &BTreeSet<u32>) -> BTreeSet<u32> {
+///     &set - removed
+/// }
+///
+/// #[derive(Union, Detach)]
+/// struct Stats {
+///     #[union(link)]
+///     size: UnionBySize,
+///     total: Sum<u32>,
+///     #[union(with = join, detach = remove)]
+///     items: BTreeSet<u32>,
+/// }
+///
+/// let stats = |n| Stats {
+///     size: UnionBySize::default(),
+///     total: Sum(n),
+///     items: BTreeSet::from([n]),
+/// };
+/// let mut uf: QuickFindUf<Stats> = [3, 1, 4].into_iter().map(stats).collect();
+/// uf.union(0, 1);
+/// uf.union(1, 2);
+/// uf.isolate(2, stats(4));
+///
+/// let stats = uf.get(0);
+/// assert_eq!(stats.size.size(), 2);
+/// assert_eq!(stats.total, Sum(4));
+/// assert_eq!(stats.items, BTreeSet::from([1, 3]));
+/// ```
+#[cfg(feature = "derive")]
+pub use union_find_derive::Detach;
+
+/// Derives `Split` for a struct that derives `Union`.
+///
+/// The link field is split by `Split::split`, and every other field is
+/// recomputed for each part by merging the values of its elements, with
+/// `Merge::merge` or the function given with `#[union(with = path)]`.
+///
+/// ```
+/// use union_find::{Min, QuickFindUf, Split, Sum, Union, UnionBySize, UnionFind};
+///
+/// #[derive(Union, Split)]
+/// struct Stats {
+///     #[union(link)]
+///     size: UnionBySize,
+///     total: Sum<usize>,
+///     low: Min<usize>,
+/// }
+///
+/// let stats = |n| Stats {
+///     size: UnionBySize::default(),
+///     total: Sum(n),
+///     low: Min(n),
+/// };
+/// let mut uf: QuickFindUf<Stats> = (0..6).map(stats).collect();
+/// for i in 0..5 {
+///     uf.union(i, i + 1);
+/// }
+///
+/// let (low, high) = uf.split_by(0, |k| k < 3, stats).unwrap();
+/// assert_eq!(uf.get(low).total, Sum(3));
+/// assert_eq!(uf.get(high).size.size(), 3);
+/// assert_eq!(uf.get(high).low, Min(3));
+/// ```
+#[cfg(feature = "derive")]
+pub use union_find_derive::Split;
+
+mod util;
+
+// Lets the derives refer to this crate as `::union_find` in tests.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as union_find;
 
 #[cfg(test)]
 mod tests;
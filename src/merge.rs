@@ -0,0 +1,64 @@
+// Copyright 2016 union-find-rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::{Merge, Unmerge};
+use std::ops::{Add, Sub};
+
+/// Merges the data of sets by adding them.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sum<T>(pub T);
+
+impl<T: Add<Output = T>> Merge for Sum<T> {
+    #[inline]
+    fn merge(left: Sum<T>, right: Sum<T>) -> Sum<T> {
+        Sum(left.0 + right.0)
+    }
+}
+
+impl<T: Add<Output = T> + Sub<Output = T> + Clone> Unmerge for Sum<T> {
+    #[inline]
+    fn unmerge(set: Sum<T>, removed: &Sum<T>) -> Sum<T> {
+        Sum(set.0 - removed.0.clone())
+    }
+}
+
+/// Merges the data of sets by taking the minimum.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Min<T>(pub T);
+
+impl<T: Ord> Merge for Min<T> {
+    #[inline]
+    fn merge(left: Min<T>, right: Min<T>) -> Min<T> {
+        Min(left.0.min(right.0))
+    }
+}
+
+/// Merges the data of sets by taking the maximum.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Max<T>(pub T);
+
+impl<T: Ord> Merge for Max<T> {
+    #[inline]
+    fn merge(left: Max<T>, right: Max<T>) -> Max<T> {
+        Max(left.0.max(right.0))
+    }
+}
+
+/// Merges the flags of sets by logical or.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Or(pub bool);
+
+impl Merge for Or {
+    #[inline]
+    fn merge(left: Or, right: Or) -> Or {
+        Or(left.0 || right.0)
+    }
+}
@@ -0,0 +1,297 @@
+// Copyright 2016 union-find-rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::{Union, UnionResult};
+use std::iter::FromIterator;
+use std::sync::Arc;
+
+const BITS: u32 = 5;
+const WIDTH: usize = 1 << BITS;
+const MASK: usize = WIDTH - 1;
+
+#[derive(Debug)]
+enum Node<T> {
+    Branch(Vec<Arc<Node<T>>>),
+    Leaf(Vec<T>),
+}
+
+/// Persistent array implemented as a path-copying trie.
+///
+/// Updates copy the nodes on the path from the root to the updated element
+/// and share all other nodes with the original array.
+#[derive(Debug)]
+struct PersistentVec<T> {
+    root: Arc<Node<T>>,
+    len: usize,
+    shift: u32,
+}
+
+impl<T> Clone for PersistentVec<T> {
+    #[inline]
+    fn clone(&self) -> PersistentVec<T> {
+        PersistentVec {
+            root: Arc::clone(&self.root),
+            len: self.len,
+            shift: self.shift,
+        }
+    }
+}
+
+impl<T: Clone> PersistentVec<T> {
+    fn new() -> PersistentVec<T> {
+        PersistentVec {
+            root: Arc::new(Node::Leaf(vec![])),
+            len: 0,
+            shift: 0,
+        }
+    }
+
+    fn get(&self, index: usize) -> &T {
+        assert!(index < self.len, "index out of bounds");
+        let mut node = &*self.root;
+        let mut level = self.shift;
+        loop {
+            match node {
+                Node::Branch(children) => {
+                    node = &children[(index >> level) & MASK];
+                    level -= BITS;
+                }
+                Node::Leaf(items) => return &items[index & MASK],
+            }
+        }
+    }
+
+    fn set(&self, index: usize, value: T) -> PersistentVec<T> {
+        fn set_node<T: Clone>(node: &Node<T>, level: u32, index: usize, value: T) -> Node<T> {
+            match node {
+                Node::Branch(children) => {
+                    let mut children = children.clone();
+                    let i = (index >> level) & MASK;
+                    children[i] = Arc::new(set_node(&children[i], level - BITS, index, value));
+                    Node::Branch(children)
+                }
+                Node::Leaf(items) => {
+                    let mut items = items.clone();
+                    items[index & MASK] = value;
+                    Node::Leaf(items)
+                }
+            }
+        }
+
+        assert!(index < self.len, "index out of bounds");
+        PersistentVec {
+            root: Arc::new(set_node(&self.root, self.shift, index, value)),
+            len: self.len,
+            shift: self.shift,
+        }
+    }
+
+    fn push(&self, value: T) -> PersistentVec<T> {
+        fn new_path<T>(level: u32, value: T) -> Node<T> {
+            if level == 0 {
+                Node::Leaf(vec![value])
+            } else {
+                Node::Branch(vec![Arc::new(new_path(level - BITS, value))])
+            }
+        }
+
+        fn push_node<T: Clone>(node: &Node<T>, level: u32, index: usize, value: T) -> Node<T> {
+            match node {
+                Node::Branch(children) => {
+                    let mut children = children.clone();
+                    let i = (index >> level) & MASK;
+                    if i < children.len() {
+                        children[i] = Arc::new(push_node(&children[i], level - BITS, index, value));
+                    } else {
+                        children.push(Arc::new(new_path(level - BITS, value)));
+                    }
+                    Node::Branch(children)
+                }
+                Node::Leaf(items) => {
+                    let mut items = items.clone();
+                    items.push(value);
+                    Node::Leaf(items)
+                }
+            }
+        }
+
+        if self.len == WIDTH << self.shift {
+            // The trie is full, so add a new level above the root.
+            let shift = self.shift + BITS;
+            let root = Node::Branch(vec![
+                Arc::clone(&self.root),
+                Arc::new(new_path(self.shift, value)),
+            ]);
+            PersistentVec {
+                root: Arc::new(root),
+                len: self.len + 1,
+                shift,
+            }
+        } else {
+            PersistentVec {
+                root: Arc::new(push_node(&self.root, self.shift, self.len, value)),
+                len: self.len + 1,
+                shift: self.shift,
+            }
+        }
+    }
+}
+
+/// Persistent Union-Find implementation.
+///
+/// Operations that modify the structure return a new version of it and leave
+/// `self` untouched. The versions share most of their internal nodes, so any
+/// number of old versions can be kept alive cheaply. `clone` takes O(1) time,
+/// and `insert` and `union` copy one path of the trie from the root, so they
+/// take O(log n) time and memory, where the base of the logarithm is the
+/// branching factor of the trie, 32.
+///
+/// The nodes are shared with `Arc`, so the versions can be sent to and shared
+/// between threads if `V` is `Send` and `Sync`.
+///
+/// Paths are never compressed, because compression would modify the shared
+/// nodes, so `find` takes O(log n) time when the sets are joined by size or
+/// rank.
+///
+/// ```
+/// use union_find::{PersistentUf, UnionBySize};
+///
+/// let base = PersistentUf::<UnionBySize>::new(4);
+/// let v1 = base.union(0, 1);
+/// let v2 = v1.union(1, 2);
+/// let v3 = v1.union(2, 3);
+///
+/// assert!(!base.connected(0, 1));
+/// assert!(v1.connected(0, 1));
+/// assert!(v2.connected(0, 2));
+/// assert!(!v3.connected(0, 2));
+/// assert!(v3.connected(2, 3));
+/// assert_eq!(v2.get(0).size(), 3);
+/// assert_eq!(v3.get(0).size(), 2);
+/// ```
+#[derive(Debug)]
+pub struct PersistentUf<V> {
+    link_parent: PersistentVec<usize>,
+    payload: PersistentVec<Option<V>>,
+    set_count: usize,
+}
+
+impl<V> Clone for PersistentUf<V> {
+    #[inline]
+    fn clone(&self) -> PersistentUf<V> {
+        PersistentUf {
+            link_parent: self.link_parent.clone(),
+            payload: self.payload.clone(),
+            set_count: self.set_count,
+        }
+    }
+}
+
+impl<V: Union + Clone> PersistentUf<V> {
+    /// Creates `PersistentUf` struct that contains `len` sets.
+    #[inline]
+    pub fn new(len: usize) -> PersistentUf<V>
+    where
+        V: Default,
+    {
+        PersistentUf::from_iter((0..len).map(|_| Default::default()))
+    }
+
+    /// Returns the size of `self`.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.payload.len
+    }
+
+    /// Returns the number of disjoint sets in `self`.
+    #[inline]
+    pub fn set_count(&self) -> usize {
+        self.set_count
+    }
+
+    /// Returns a new version of `self` with a new set inserted, and the key of
+    /// the inserted set.
+    #[inline]
+    pub fn insert(&self, data: V) -> (PersistentUf<V>, usize) {
+        let key = self.payload.len;
+        let uf = PersistentUf {
+            link_parent: self.link_parent.push(key),
+            payload: self.payload.push(Some(data)),
+            set_count: self.set_count + 1,
+        };
+        (uf, key)
+    }
+
+    /// Returns a new version of `self` with the two sets that contains given
+    /// keys joined (union operation).
+    ///
+    /// If the keys already belong to the same set, the returned version is
+    /// identical to `self`.
+    #[inline]
+    pub fn union(&self, key0: usize, key1: usize) -> PersistentUf<V> {
+        let k0 = self.find(key0);
+        let k1 = self.find(key1);
+        if k0 == k1 {
+            return self.clone();
+        }
+
+        let v0 = self.payload.get(k0).clone().unwrap();
+        let v1 = self.payload.get(k1).clone().unwrap();
+
+        let (parent, child, val) = match Union::union(v0, v1) {
+            UnionResult::Left(val) => (k0, k1, val),
+            UnionResult::Right(val) => (k1, k0, val),
+        };
+        PersistentUf {
+            link_parent: self.link_parent.set(child, parent),
+            payload: self.payload.set(parent, Some(val)).set(child, None),
+            set_count: self.set_count - 1,
+        }
+    }
+
+    /// Returns the identifier of the set that the key belongs to.
+    #[inline]
+    pub fn find(&self, key: usize) -> usize {
+        let mut k = key;
+        let mut p = *self.link_parent.get(k);
+        while p != k {
+            k = p;
+            p = *self.link_parent.get(k);
+        }
+        k
+    }
+
+    /// Returns `true` if given keys belong to the same set.
+    #[inline]
+    pub fn connected(&self, key0: usize, key1: usize) -> bool {
+        self.find(key0) == self.find(key1)
+    }
+
+    /// Returns the reference to the value of the set that the key belongs to.
+    #[inline]
+    pub fn get(&self, key: usize) -> &V {
+        let root_key = self.find(key);
+        self.payload.get(root_key).as_ref().unwrap()
+    }
+}
+
+impl<A: Union + Clone> FromIterator<A> for PersistentUf<A> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = A>>(iterator: T) -> PersistentUf<A> {
+        let mut link_parent = PersistentVec::new();
+        let mut payload = PersistentVec::new();
+        for data in iterator {
+            link_parent = link_parent.push(payload.len);
+            payload = payload.push(Some(data));
+        }
+        PersistentUf {
+            link_parent,
+            set_count: payload.len,
+            payload,
+        }
+    }
+}
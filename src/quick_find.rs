@@ -5,136 +5,493 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use crate::{Union, UnionFind, UnionResult};
+use crate::{util, Detach, Idx, Split, UfError, Union, UnionFind, UnionOutcome, UnionResult};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::iter::FromIterator;
 
 #[derive(Copy, Clone, Debug)]
-struct Payload<V> {
+struct Payload<V, K> {
     data: V,
-    link_last_child: usize,
+    link_last_child: K,
+    len: usize,
 }
 
 /// Union-Find implementation with quick find operation.
+///
+/// With the `serde` feature, `self` can be serialized and deserialized.
+/// Only the roots and the values are stored, and the sibling chains are
+/// rebuilt on deserialization, listing the members other than the root in
+/// ascending order. Deserialization checks that every key links to a root
+/// and that exactly the roots have values, and fails otherwise.
 #[derive(Debug)]
-pub struct QuickFindUf<V> {
-    link_root: Vec<usize>,
-    link_sibling: Vec<usize>,
-    payload: Vec<Option<Payload<V>>>,
+pub struct QuickFindUf<V, K = usize> {
+    link_root: Vec<K>,
+    link_sibling: Vec<K>,
+    payload: Vec<Option<Payload<V, K>>>,
+    set_count: usize,
 }
 
-impl<V> Clone for QuickFindUf<V>
+impl<V, K> Clone for QuickFindUf<V, K>
 where
-    V: Clone + Union,
+    V: Clone,
+    K: Clone,
 {
     #[inline]
-    fn clone(&self) -> QuickFindUf<V> {
+    fn clone(&self) -> QuickFindUf<V, K> {
         QuickFindUf {
             link_root: self.link_root.clone(),
             link_sibling: self.link_sibling.clone(),
             payload: self.payload.clone(),
+            set_count: self.set_count,
         }
     }
 
     #[inline]
-    fn clone_from(&mut self, other: &QuickFindUf<V>) {
+    fn clone_from(&mut self, other: &QuickFindUf<V, K>) {
         self.link_root.clone_from(&other.link_root);
         self.link_sibling.clone_from(&other.link_sibling);
         self.payload.clone_from(&other.payload);
+        self.set_count = other.set_count;
     }
 }
 
-impl<V: Union> UnionFind<V> for QuickFindUf<V> {
+impl<V, K: Idx> UnionFind<V, K> for QuickFindUf<V, K> {
     #[inline]
     fn size(&self) -> usize {
         self.payload.len()
     }
 
     #[inline]
-    fn insert(&mut self, data: V) -> usize {
-        let key = self.payload.len();
+    fn set_count(&self) -> usize {
+        self.set_count
+    }
+
+    #[inline]
+    fn insert(&mut self, data: V) -> K {
+        let key = K::from_index(self.payload.len());
         self.link_root.push(key);
         self.link_sibling.push(key);
         self.payload.push(Some(Payload {
             data,
             link_last_child: key,
+            len: 1,
         }));
+        self.set_count += 1;
         key
     }
 
     #[inline]
-    fn union(&mut self, key0: usize, key1: usize) -> bool {
+    fn union(&mut self, key0: K, key1: K) -> bool
+    where
+        V: Union,
+    {
+        self.union_detailed(key0, key1).is_merged()
+    }
+
+    #[inline]
+    fn union_detailed(&mut self, key0: K, key1: K) -> UnionOutcome<K>
+    where
+        V: Union,
+    {
         let k0 = self.find(key0);
         let k1 = self.find(key1);
         if k0 == k1 {
-            return false;
+            return UnionOutcome::AlreadyJoined { root: k0 };
         }
 
         // Temporary replace with dummy to move out the elements of the vector.
         let Payload {
             data: d0,
             link_last_child: c0,
-        } = self.payload[k0].take().unwrap();
+            len: n0,
+        } = self.payload[k0.index()].take().unwrap();
         let Payload {
             data: d1,
             link_last_child: c1,
-        } = self.payload[k1].take().unwrap();
+            len: n1,
+        } = self.payload[k1.index()].take().unwrap();
 
         let (root, child_root, val, last) = match Union::union(d0, d1) {
             UnionResult::Left(val) => (k0, k1, val, c0),
             UnionResult::Right(val) => (k1, k0, val, c1),
         };
+        self.link(root, child_root, last, val, n0 + n1);
+
+        UnionOutcome::Merged {
+            root,
+            absorbed: child_root,
+        }
+    }
+
+    #[inline]
+    fn find(&mut self, key: K) -> K {
+        self.link_root[key.index()]
+    }
 
-        self.link_sibling[last] = child_root;
+    #[inline]
+    fn get(&mut self, key: K) -> &V {
+        let root_key = self.find(key);
+        &self.payload[root_key.index()].as_ref().unwrap().data
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: K) -> &mut V {
+        let root_key = self.find(key);
+        &mut self.payload[root_key.index()].as_mut().unwrap().data
+    }
+
+    #[inline]
+    fn find_immutable(&self, key: K) -> K {
+        self.link_root[key.index()]
+    }
+
+    #[inline]
+    fn get_immutable(&self, key: K) -> &V {
+        let root_key = self.find_immutable(key);
+        &self.payload[root_key.index()].as_ref().unwrap().data
+    }
+
+    #[inline]
+    fn try_union(&mut self, key0: K, key1: K) -> Result<bool, UfError>
+    where
+        V: Union,
+    {
+        let k0 = self.try_find(key0)?.index();
+        let k1 = self.try_find(key1)?.index();
+        if k0 == k1 {
+            return Ok(false);
+        }
+        for key in [k0, k1] {
+            if self.payload[key].is_none() {
+                return Err(UfError::MissingPayload { key });
+            }
+        }
+        Ok(self.union(key0, key1))
+    }
+
+    #[inline]
+    fn try_get(&mut self, key: K) -> Result<&V, UfError> {
+        let root_key = self.try_find(key)?.index();
+        self.payload[root_key]
+            .as_ref()
+            .map(|payload| &payload.data)
+            .ok_or(UfError::MissingPayload { key: root_key })
+    }
+
+    #[inline]
+    fn try_get_mut(&mut self, key: K) -> Result<&mut V, UfError> {
+        let root_key = self.try_find(key)?.index();
+        self.payload[root_key]
+            .as_mut()
+            .map(|payload| &mut payload.data)
+            .ok_or(UfError::MissingPayload { key: root_key })
+    }
+}
+
+impl<V, K: Idx> QuickFindUf<V, K> {
+    /// Returns an iterator over the keys of the set that the key belongs to.
+    ///
+    /// This walks the sibling chain of the set, so it takes time proportional
+    /// to the size of the set rather than the size of `self`.
+    #[inline]
+    pub fn members(&self, key: K) -> Members<'_, K> {
+        let root = self.link_root[key.index()];
+        let last = self.payload[root.index()].as_ref().unwrap().link_last_child;
+        Members::new(&self.link_sibling, root, last)
+    }
+
+    /// Returns an iterator over all sets in `self`.
+    ///
+    /// Each item is a tuple of the identifier of the set, the reference to
+    /// the value of the set and the keys that belong to the set.
+    /// The sets are yielded in ascending order of their identifiers.
+    #[inline]
+    pub fn sets(&self) -> impl Iterator<Item = (K, &V, Vec<K>)> + '_ {
+        self.payload
+            .iter()
+            .enumerate()
+            .filter_map(|(key, payload)| {
+                let key = K::from_index(key);
+                payload
+                    .as_ref()
+                    .map(|payload| (key, &payload.data, self.members(key).collect()))
+            })
+    }
+
+    /// Returns an iterator over the identifiers of all sets in `self`.
+    #[inline]
+    pub fn roots(&self) -> impl Iterator<Item = K> + '_ {
+        self.payload
+            .iter()
+            .enumerate()
+            .filter_map(|(key, payload)| payload.as_ref().map(|_| K::from_index(key)))
+    }
+
+    /// Returns the dense index of the set that each key belongs to.
+    ///
+    /// The sets are numbered from `0` to `set_count() - 1` in the order that
+    /// their first key appears.
+    #[inline]
+    pub fn canonical_labels(&self) -> Vec<usize> {
+        util::dense_labels(&self.link_root)
+    }
+
+    /// Join two sets that contains given keys, merging their values with `f`.
+    ///
+    /// `f` is called with the values of the sets of `key0` and `key1`, in
+    /// this order. Unlike `union`, the values do not need to implement
+    /// `Union`: the members of the smaller set are always moved into the
+    /// larger set, so that each key is moved O(log n) times in total.
+    ///
+    /// Returns `true` if these keys are belonged to different sets.
+    ///
+    /// ```
+    /// use union_find::{QuickFindUf, UnionFind};
+    ///
+    /// let mut uf: QuickFindUf<Vec<&str>> = vec![vec!["a"], vec!["b"], vec!["c"]]
+    ///     .into_iter()
+    ///     .collect();
+    /// assert!(uf.union_with(0, 1, |mut l, r| {
+    ///     l.extend(r);
+    ///     l
+    /// }));
+    /// assert_eq!(uf.get(1), &["a", "b"]);
+    /// ```
+    #[inline]
+    pub fn union_with<F>(&mut self, key0: K, key1: K, f: F) -> bool
+    where
+        F: FnOnce(V, V) -> V,
+    {
+        let k0 = self.find(key0);
+        let k1 = self.find(key1);
+        if k0 == k1 {
+            return false;
+        }
+
+        // Temporary replace with dummy to move out the elements of the vector.
+        let Payload {
+            data: d0,
+            link_last_child: c0,
+            len: n0,
+        } = self.payload[k0.index()].take().unwrap();
+        let Payload {
+            data: d1,
+            link_last_child: c1,
+            len: n1,
+        } = self.payload[k1.index()].take().unwrap();
+
+        let val = f(d0, d1);
+        let (root, child_root, last) = if n0 >= n1 { (k0, k1, c0) } else { (k1, k0, c1) };
+        self.link(root, child_root, last, val, n0 + n1);
+
+        true
+    }
+
+    /// Appends the members of the set `child_root` after `last`, the last
+    /// member of the set `root`, and stores the value of the joined set.
+    ///
+    /// The values of both sets must have been taken out.
+    fn link(&mut self, root: K, child_root: K, last: K, data: V, len: usize) {
+        self.link_sibling[last.index()] = child_root;
 
         let mut elem = child_root;
-        while self.link_sibling[elem] != elem {
-            debug_assert_eq!(self.link_root[elem], child_root);
-            self.link_root[elem] = root;
-            elem = self.link_sibling[elem];
+        while self.link_sibling[elem.index()] != elem {
+            debug_assert_eq!(self.link_root[elem.index()], child_root);
+            self.link_root[elem.index()] = root;
+            elem = self.link_sibling[elem.index()];
         }
-        debug_assert_eq!(self.link_root[elem], child_root);
-        self.link_root[elem] = root;
+        debug_assert_eq!(self.link_root[elem.index()], child_root);
+        self.link_root[elem.index()] = root;
 
-        self.payload[root] = Some(Payload {
-            data: val,
+        self.payload[root.index()] = Some(Payload {
+            data,
             link_last_child: elem,
+            len,
         });
 
-        true
+        self.set_count -= 1;
     }
+}
 
+impl<V: Detach, K: Idx> QuickFindUf<V, K> {
+    /// Moves the element of the key out of its set into a new singleton set
+    /// whose value is `data`.
+    ///
+    /// `data` is the value of the element itself. The key is unlinked from
+    /// the sibling chain of its former set, and `data` is removed from the
+    /// value of the former set by `Detach::detach`. This takes time
+    /// proportional to the size of the former set.
+    ///
+    /// ```
+    /// use union_find::{QuickFindUf, UnionBySize, UnionFind};
+    ///
+    /// let mut uf = QuickFindUf::<UnionBySize>::new(3);
+    /// assert!(uf.union(0, 1));
+    /// assert!(uf.union(1, 2));
+    ///
+    /// uf.isolate(0, UnionBySize::default());
+    /// assert!(!uf.connected(0, 1));
+    /// assert_eq!(uf.get(1).size(), 2);
+    /// assert_eq!(uf.members(1).collect::<Vec<_>>(), [1, 2]);
+    /// ```
     #[inline]
-    fn find(&mut self, key: usize) -> usize {
-        self.link_root[key]
+    pub fn isolate(&mut self, key: K, data: V) {
+        let root = self.link_root[key.index()];
+        let Payload {
+            data: set,
+            link_last_child: last,
+            len,
+        } = self.payload[root.index()].take().unwrap();
+
+        if root == last {
+            // The set only contains the key.
+            self.payload[root.index()] = Some(Payload {
+                data,
+                link_last_child: key,
+                len: 1,
+            });
+            return;
+        }
+
+        let set = Detach::detach(set, &data);
+        if key == root {
+            // The next member becomes the new root of the former set.
+            let new_root = self.link_sibling[key.index()];
+            let mut elem = new_root;
+            loop {
+                self.link_root[elem.index()] = new_root;
+                if elem == last {
+                    break;
+                }
+                elem = self.link_sibling[elem.index()];
+            }
+            self.payload[new_root.index()] = Some(Payload {
+                data: set,
+                link_last_child: last,
+                len: len - 1,
+            });
+        } else {
+            let mut prev = root;
+            while self.link_sibling[prev.index()] != key {
+                prev = self.link_sibling[prev.index()];
+            }
+            let last = if key == last {
+                self.link_sibling[prev.index()] = prev;
+                prev
+            } else {
+                self.link_sibling[prev.index()] = self.link_sibling[key.index()];
+                last
+            };
+            self.payload[root.index()] = Some(Payload {
+                data: set,
+                link_last_child: last,
+                len: len - 1,
+            });
+        }
+
+        self.link_root[key.index()] = key;
+        self.link_sibling[key.index()] = key;
+        self.payload[key.index()] = Some(Payload {
+            data,
+            link_last_child: key,
+            len: 1,
+        });
+        self.set_count += 1;
     }
+}
 
+impl<V: Split, K: Idx> QuickFindUf<V, K> {
+    /// Splits the set that the key belongs to into the members that satisfy
+    /// the predicate and the others.
+    ///
+    /// Returns the identifiers of the two sets, or `None` without modifying
+    /// `self` if all members are on the same side. `value` returns the value
+    /// of each member itself, and the values of the sets are computed from
+    /// them by `Split::split`. The members of each set are relinked in
+    /// ascending order, so the identifier of each set is its smallest key.
+    /// This takes time proportional to the size of the set.
+    ///
+    /// ```
+    /// use union_find::{QuickFindUf, Sum, UnionBySize, UnionFind};
+    ///
+    /// let mut uf: QuickFindUf<(UnionBySize, Sum<usize>)> = (0..5)
+    ///     .map(|n| (UnionBySize::default(), Sum(n)))
+    ///     .collect();
+    /// for i in 0..4 {
+    ///     uf.union(i, i + 1);
+    /// }
+    ///
+    /// let value = |k| (UnionBySize::default(), Sum(k));
+    /// let (even, odd) = uf.split_by(0, |k| k % 2 == 0, value).unwrap();
+    /// assert_eq!(uf.members(even).collect::<Vec<_>>(), [0, 2, 4]);
+    /// assert_eq!(uf.members(odd).collect::<Vec<_>>(), [1, 3]);
+    /// assert_eq!(uf.get(even).0.size(), 3);
+    /// assert_eq!(uf.get(odd).1, Sum(4));
+    ///
+    /// assert_eq!(uf.split_by(1, |k| k < 10, value), None);
+    /// ```
     #[inline]
-    fn get(&mut self, key: usize) -> &V {
-        let root_key = self.find(key);
-        &self.payload[root_key].as_ref().unwrap().data
+    pub fn split_by<F, G>(&mut self, key: K, mut pred: F, mut value: G) -> Option<(K, K)>
+    where
+        F: FnMut(K) -> bool,
+        G: FnMut(K) -> V,
+    {
+        let (mut part0, mut part1): (Vec<K>, Vec<K>) = self.members(key).partition(|&k| pred(k));
+        if part0.is_empty() || part1.is_empty() {
+            return None;
+        }
+        part0.sort_unstable_by_key(|k| k.index());
+        part1.sort_unstable_by_key(|k| k.index());
+
+        let values0 = part0.iter().map(|&k| value(k)).collect();
+        let values1 = part1.iter().map(|&k| value(k)).collect();
+        let root = self.link_root[key.index()];
+        let set = self.payload[root.index()].take().unwrap().data;
+        let (d0, d1) = Split::split(set, values0, values1);
+        let r0 = self.relink(&part0, d0);
+        let r1 = self.relink(&part1, d1);
+
+        self.set_count += 1;
+
+        Some((r0, r1))
     }
 
-    #[inline]
-    fn get_mut(&mut self, key: usize) -> &mut V {
-        let root_key = self.find(key);
-        &mut self.payload[root_key].as_mut().unwrap().data
+    /// Makes the keys into a set whose root is the first key.
+    fn relink(&mut self, keys: &[K], data: V) -> K {
+        let root = keys[0];
+        for pair in keys.windows(2) {
+            self.link_sibling[pair[0].index()] = pair[1];
+        }
+        let last = keys[keys.len() - 1];
+        self.link_sibling[last.index()] = last;
+        for &k in keys {
+            self.link_root[k.index()] = root;
+        }
+        self.payload[root.index()] = Some(Payload {
+            data,
+            link_last_child: last,
+            len: keys.len(),
+        });
+        root
     }
 }
 
-impl<A: Union> FromIterator<A> for QuickFindUf<A> {
+impl<A, K: Idx> FromIterator<A> for QuickFindUf<A, K> {
     #[inline]
-    fn from_iter<T: IntoIterator<Item = A>>(iterator: T) -> QuickFindUf<A> {
+    fn from_iter<T: IntoIterator<Item = A>>(iterator: T) -> QuickFindUf<A, K> {
         let mut uf = QuickFindUf {
             link_root: vec![],
             link_sibling: vec![],
             payload: vec![],
+            set_count: 0,
         };
         uf.extend(iterator);
         uf
     }
 }
 
-impl<A> Extend<A> for QuickFindUf<A> {
+impl<A, K: Idx> Extend<A> for QuickFindUf<A, K> {
     #[inline]
     fn extend<T>(&mut self, iterable: T)
     where
@@ -146,13 +503,145 @@ impl<A> Extend<A> for QuickFindUf<A> {
             .zip(len..)
             .map(|(data, link)| Payload {
                 data,
-                link_last_child: link,
+                link_last_child: K::from_index(link),
+                len: 1,
             })
             .map(Some);
         self.payload.extend(payload);
 
         let new_len = self.payload.len();
-        self.link_root.extend(len..new_len);
-        self.link_sibling.extend(len..new_len);
+        self.link_root.extend((len..new_len).map(K::from_index));
+        self.link_sibling.extend((len..new_len).map(K::from_index));
+        self.set_count += new_len - len;
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+#[serde(bound = "V: Serialize")]
+struct QuickFindRef<'a, V, K: Idx> {
+    link_root: util::Indices<'a, K>,
+    payload: Vec<Option<&'a V>>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct QuickFindRepr<V> {
+    link_root: Vec<usize>,
+    payload: Vec<Option<V>>,
+}
+
+#[cfg(feature = "serde")]
+impl<V: Serialize, K: Idx> Serialize for QuickFindUf<V, K> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        QuickFindRef {
+            link_root: util::Indices(&self.link_root),
+            payload: self
+                .payload
+                .iter()
+                .map(|payload| payload.as_ref().map(|payload| &payload.data))
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, V: Deserialize<'de>, K: Idx> Deserialize<'de> for QuickFindUf<V, K> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = QuickFindRepr::<V>::deserialize(deserializer)?;
+        let len = repr.link_root.len();
+        if len != repr.payload.len() {
+            return Err(de::Error::custom(
+                "`link_root` and `payload` have different lengths",
+            ));
+        }
+        for (key, (&root, payload)) in repr.link_root.iter().zip(&repr.payload).enumerate() {
+            if root >= len {
+                return Err(de::Error::custom("root link out of range"));
+            }
+            if repr.link_root[root] != root {
+                return Err(de::Error::custom("root link to non-root key"));
+            }
+            match (root == key, payload.is_some()) {
+                (true, false) => return Err(de::Error::custom("root without payload")),
+                (false, true) => return Err(de::Error::custom("payload on non-root key")),
+                _ => {}
+            }
+        }
+
+        // Append each key to the chain of its root, which starts at the root.
+        let mut link_last = (0..len).collect::<Vec<_>>();
+        let mut link_sibling = (0..len).map(K::from_index).collect::<Vec<_>>();
+        let mut set_len = vec![1; len];
+        for (key, &root) in repr.link_root.iter().enumerate() {
+            if key != root {
+                link_sibling[link_last[root]] = K::from_index(key);
+                link_last[root] = key;
+                set_len[root] += 1;
+            }
+        }
+
+        let mut set_count = 0;
+        let payload = repr
+            .payload
+            .into_iter()
+            .zip(link_last.into_iter().zip(set_len))
+            .map(|(data, (last, len))| {
+                data.map(|data| {
+                    set_count += 1;
+                    Payload {
+                        data,
+                        link_last_child: K::from_index(last),
+                        len,
+                    }
+                })
+            })
+            .collect();
+
+        Ok(QuickFindUf {
+            link_root: repr.link_root.into_iter().map(K::from_index).collect(),
+            link_sibling,
+            payload,
+            set_count,
+        })
+    }
+}
+
+/// An iterator over the keys of a set.
+///
+/// This `struct` is created by the `members` method of the union-find
+/// structures.
+#[derive(Clone, Debug)]
+pub struct Members<'a, K = usize> {
+    link_next: &'a [K],
+    next: Option<K>,
+    last: K,
+}
+
+impl<'a, K> Members<'a, K> {
+    /// Creates an iterator that follows `link_next` from `first` until `last`.
+    #[inline]
+    pub(crate) fn new(link_next: &'a [K], first: K, last: K) -> Members<'a, K> {
+        Members {
+            link_next,
+            next: Some(first),
+            last,
+        }
+    }
+}
+
+impl<K: Idx> Iterator for Members<'_, K> {
+    type Item = K;
+
+    #[inline]
+    fn next(&mut self) -> Option<K> {
+        let key = self.next?;
+        self.next = if key == self.last {
+            None
+        } else {
+            Some(self.link_next[key.index()])
+        };
+        Some(key)
     }
 }
@@ -5,52 +5,118 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use crate::{Union, UnionFind, UnionResult};
+use crate::binary::{self, Header};
+use crate::{util, Codec, Detach, Idx, UfError, Union, UnionFind, UnionOutcome, UnionResult};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::io::{self, Read, Write};
 use std::iter::FromIterator;
+use std::mem;
 
 /// Union-Find implementation with quick union operation.
+///
+/// With the `serde` feature, `self` can be serialized and deserialized.
+/// Deserialization checks that the parent links form a forest and that
+/// exactly the roots have values, and fails otherwise.
 #[derive(Debug)]
-pub struct QuickUnionUf<V> {
-    link_parent: Vec<usize>,
+pub struct QuickUnionUf<V, K = usize> {
+    // The links and the values are indexed by nodes. Each key is its own
+    // node until `isolate` is called for the first time, which fills the
+    // tables below.
+    link_parent: Vec<K>,
     payload: Vec<Option<V>>,
+    // An upper bound of the height of the tree of each root, which is used
+    // by `union_with` to choose the parent. It is empty until `union_with` is
+    // called for the first time.
+    rank: Vec<u8>,
+    set_count: usize,
+    // The node of each key.
+    link_node: Vec<K>,
+    // The key of each node. A node is a placeholder left by `isolate` if its
+    // key has moved to another node.
+    link_owner: Vec<K>,
+    // The next node in the circular list of the nodes of each set.
+    link_next: Vec<K>,
 }
 
-impl<V: Clone> Clone for QuickUnionUf<V> {
+impl<V: Clone, K: Clone> Clone for QuickUnionUf<V, K> {
     #[inline]
-    fn clone(&self) -> QuickUnionUf<V> {
+    fn clone(&self) -> QuickUnionUf<V, K> {
         QuickUnionUf {
             link_parent: self.link_parent.clone(),
             payload: self.payload.clone(),
+            rank: self.rank.clone(),
+            set_count: self.set_count,
+            link_node: self.link_node.clone(),
+            link_owner: self.link_owner.clone(),
+            link_next: self.link_next.clone(),
         }
     }
 
     #[inline]
-    fn clone_from(&mut self, other: &QuickUnionUf<V>) {
+    fn clone_from(&mut self, other: &QuickUnionUf<V, K>) {
         self.link_parent.clone_from(&other.link_parent);
         self.payload.clone_from(&other.payload);
+        self.rank.clone_from(&other.rank);
+        self.set_count = other.set_count;
+        self.link_node.clone_from(&other.link_node);
+        self.link_owner.clone_from(&other.link_owner);
+        self.link_next.clone_from(&other.link_next);
     }
 }
 
-impl<V: Union> UnionFind<V> for QuickUnionUf<V> {
+impl<V, K: Idx> UnionFind<V, K> for QuickUnionUf<V, K> {
     #[inline]
     fn size(&self) -> usize {
-        self.payload.len()
+        if self.link_node.is_empty() {
+            self.payload.len()
+        } else {
+            self.link_node.len()
+        }
+    }
+
+    #[inline]
+    fn set_count(&self) -> usize {
+        self.set_count
     }
 
     #[inline]
-    fn insert(&mut self, data: V) -> usize {
-        let key = self.payload.len();
-        self.link_parent.push(key);
+    fn insert(&mut self, data: V) -> K {
+        let key = K::from_index(self.size());
+        let node = K::from_index(self.link_parent.len());
+        self.link_parent.push(node);
         self.payload.push(Some(data));
+        if !self.rank.is_empty() {
+            self.rank.push(0);
+        }
+        if !self.link_node.is_empty() {
+            self.link_node.push(node);
+            self.link_owner.push(key);
+            self.link_next.push(node);
+        }
+        self.set_count += 1;
         key
     }
 
     #[inline]
-    fn union(&mut self, key0: usize, key1: usize) -> bool {
-        let k0 = self.find(key0);
-        let k1 = self.find(key1);
+    fn union(&mut self, key0: K, key1: K) -> bool
+    where
+        V: Union,
+    {
+        self.union_detailed(key0, key1).is_merged()
+    }
+
+    #[inline]
+    fn union_detailed(&mut self, key0: K, key1: K) -> UnionOutcome<K>
+    where
+        V: Union,
+    {
+        let k0 = self.find_root(self.node_of(key0));
+        let k1 = self.find_root(self.node_of(key1));
         if k0 == k1 {
-            return false;
+            return UnionOutcome::AlreadyJoined {
+                root: self.owner_of(k0),
+            };
         }
 
         // Temporary replace with dummy to move out the elements of the vector.
@@ -61,61 +127,669 @@ impl<V: Union> UnionFind<V> for QuickUnionUf<V> {
             UnionResult::Left(val) => (k0, k1, val),
             UnionResult::Right(val) => (k1, k0, val),
         };
-        self.payload[parent] = Some(val);
-        self.link_parent[child] = parent;
+        self.link(parent, child, val);
+
+        UnionOutcome::Merged {
+            root: self.owner_of(parent),
+            absorbed: self.owner_of(child),
+        }
+    }
+
+    #[inline]
+    fn find(&mut self, key: K) -> K {
+        let root = self.find_root(self.node_of(key));
+        self.owner_of(root)
+    }
+
+    #[inline]
+    fn get(&mut self, key: K) -> &V {
+        let root = self.find_root(self.node_of(key));
+        self.payload[root].as_ref().unwrap()
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: K) -> &mut V {
+        let root = self.find_root(self.node_of(key));
+        self.payload[root].as_mut().unwrap()
+    }
+
+    #[inline]
+    fn find_immutable(&self, key: K) -> K {
+        let root = self.find_root_immutable(self.node_of(key));
+        self.owner_of(root)
+    }
+
+    #[inline]
+    fn get_immutable(&self, key: K) -> &V {
+        let root = self.find_root_immutable(self.node_of(key));
+        self.payload[root].as_ref().unwrap()
+    }
+
+    #[inline]
+    fn try_union(&mut self, key0: K, key1: K) -> Result<bool, UfError>
+    where
+        V: Union,
+    {
+        let k0 = self.try_find_root(key0)?;
+        let k1 = self.try_find_root(key1)?;
+        if k0 == k1 {
+            return Ok(false);
+        }
+        for root in [k0, k1] {
+            if self.payload[root].is_none() {
+                return Err(UfError::MissingPayload {
+                    key: self.owner_of(root).index(),
+                });
+            }
+        }
+        Ok(self.union(key0, key1))
+    }
+
+    #[inline]
+    fn try_get(&mut self, key: K) -> Result<&V, UfError> {
+        let root = self.try_find_root(key)?;
+        let key = self.owner_of(root).index();
+        self.payload[root]
+            .as_ref()
+            .ok_or(UfError::MissingPayload { key })
+    }
+
+    #[inline]
+    fn try_get_mut(&mut self, key: K) -> Result<&mut V, UfError> {
+        let root = self.try_find_root(key)?;
+        let key = self.owner_of(root).index();
+        self.payload[root]
+            .as_mut()
+            .ok_or(UfError::MissingPayload { key })
+    }
+}
+
+impl<V, K: Idx> QuickUnionUf<V, K> {
+    /// Returns an iterator over all sets in `self`.
+    ///
+    /// Each item is a tuple of the identifier of the set, the reference to
+    /// the value of the set and the keys that belong to the set.
+    /// The sets are yielded in ascending order of their identifiers.
+    #[inline]
+    pub fn sets(&self) -> impl Iterator<Item = (K, &V, Vec<K>)> + '_ {
+        let roots = self.root_table();
+        let mut members = vec![vec![]; roots.len()];
+        for key in 0..self.size() {
+            members[roots[self.node_of(K::from_index(key))]].push(K::from_index(key));
+        }
+        (0..self.size()).filter_map(move |key| {
+            let key = K::from_index(key);
+            let node = self.node_of(key);
+            if roots[node] != node {
+                return None;
+            }
+            let data = self.payload[node].as_ref()?;
+            Some((key, data, mem::take(&mut members[node])))
+        })
+    }
+
+    /// Returns an iterator over the identifiers of all sets in `self`.
+    #[inline]
+    pub fn roots(&self) -> impl Iterator<Item = K> + '_ {
+        (0..self.size()).map(K::from_index).filter(|&key| {
+            let node = self.node_of(key);
+            self.link_parent[node].index() == node && self.payload[node].is_some()
+        })
+    }
+
+    /// Returns the dense index of the set that each key belongs to.
+    ///
+    /// The sets are numbered from `0` to `set_count() - 1` in the order that
+    /// their first key appears.
+    #[inline]
+    pub fn canonical_labels(&self) -> Vec<usize> {
+        let roots = self.root_table();
+        let identifiers = (0..self.size())
+            .map(|key| self.owner_of(roots[self.node_of(K::from_index(key))]))
+            .collect::<Vec<_>>();
+        util::dense_labels(&identifiers)
+    }
+
+    /// Join two sets that contains given keys, merging their values with `f`.
+    ///
+    /// `f` is called with the values of the sets of `key0` and `key1`, in
+    /// this order. Unlike `union`, the values do not need to implement
+    /// `Union`: the sets are joined by the ranks that `self` keeps for
+    /// them, so the trees stay shallow whatever the values are. The first
+    /// call allocates the ranks and takes O(n) time.
+    ///
+    /// Returns `true` if these keys are belonged to different sets.
+    ///
+    /// ```
+    /// use union_find::{QuickUnionUf, UnionFind};
+    ///
+    /// let mut uf: QuickUnionUf<u32> = (0..4).collect();
+    /// assert!(uf.union_with(0, 1, |l, r| l + r));
+    /// assert!(uf.union_with(2, 3, |l, r| l + r));
+    /// assert!(uf.union_with(1, 3, |l, r| l + r));
+    /// assert_eq!(uf.get(0), &6);
+    /// ```
+    #[inline]
+    pub fn union_with<F>(&mut self, key0: K, key1: K, f: F) -> bool
+    where
+        F: FnOnce(V, V) -> V,
+    {
+        let k0 = self.find_root(self.node_of(key0));
+        let k1 = self.find_root(self.node_of(key1));
+        if k0 == k1 {
+            return false;
+        }
+
+        // Temporary replace with dummy to move out the elements of the vector.
+        let v0 = self.payload[k0].take().unwrap();
+        let v1 = self.payload[k1].take().unwrap();
+
+        let val = f(v0, v1);
+        if self.rank.is_empty() {
+            self.rank = self.rank_table();
+        }
+        let (parent, child) = if self.rank[k0] >= self.rank[k1] {
+            (k0, k1)
+        } else {
+            (k1, k0)
+        };
+        self.link(parent, child, val);
 
         true
     }
 
+    /// Returns the number of the nodes, including the placeholders left by
+    /// `isolate`.
+    #[cfg(test)]
+    pub(crate) fn node_count(&self) -> usize {
+        self.link_parent.len()
+    }
+
+    /// Returns the node of the key.
+    #[inline]
+    fn node_of(&self, key: K) -> usize {
+        if self.link_node.is_empty() {
+            key.index()
+        } else {
+            self.link_node[key.index()].index()
+        }
+    }
+
+    /// Returns the key of the node.
+    #[inline]
+    fn owner_of(&self, node: usize) -> K {
+        if self.link_owner.is_empty() {
+            K::from_index(node)
+        } else {
+            self.link_owner[node]
+        }
+    }
+
+    /// Returns `true` if the node has not been left as a placeholder by
+    /// `isolate`.
+    #[inline]
+    fn is_owned(&self, node: usize) -> bool {
+        self.node_of(self.owner_of(node)) == node
+    }
+
     #[inline]
-    fn find(&mut self, key: usize) -> usize {
-        let mut k = key;
-        let mut p = self.link_parent[k];
+    fn find_root(&mut self, node: usize) -> usize {
+        let mut k = node;
+        let mut p = self.link_parent[k].index();
         while p != k {
             let pp = self.link_parent[p];
             self.link_parent[k] = pp;
             k = p;
-            p = pp;
+            p = pp.index();
         }
         k
     }
 
     #[inline]
-    fn get(&mut self, key: usize) -> &V {
-        let root_key = self.find(key);
-        self.payload[root_key].as_ref().unwrap()
+    fn find_root_immutable(&self, node: usize) -> usize {
+        let mut k = node;
+        let mut p = self.link_parent[k].index();
+        while p != k {
+            k = p;
+            p = self.link_parent[k].index();
+        }
+        k
     }
 
     #[inline]
-    fn get_mut(&mut self, key: usize) -> &mut V {
-        let root_key = self.find(key);
-        self.payload[root_key].as_mut().unwrap()
+    fn try_find_root(&mut self, key: K) -> Result<usize, UfError> {
+        let _ = self.try_find(key)?;
+        Ok(self.find_root(self.node_of(key)))
+    }
+
+    /// Links the root `child` under the root `parent`, and stores the value
+    /// of the joined set.
+    ///
+    /// The values of both sets must have been taken out.
+    #[inline]
+    fn link(&mut self, parent: usize, child: usize, data: V) {
+        self.payload[parent] = Some(data);
+        self.link_parent[child] = K::from_index(parent);
+        if !self.rank.is_empty() {
+            self.rank[parent] = self.rank[parent].max(self.rank[child].saturating_add(1));
+        }
+        if !self.link_next.is_empty() {
+            // Swapping the links of two nodes in different lists splices the
+            // lists into one.
+            self.link_next.swap(parent, child);
+        }
+
+        self.set_count -= 1;
+    }
+
+    /// Returns the indices of the roots of the sets that each node belongs
+    /// to.
+    ///
+    /// The roots found for the nodes are memoized, so this takes O(n) time
+    /// in total without modifying `self`.
+    fn root_table(&self) -> Vec<usize> {
+        const UNKNOWN: usize = usize::MAX;
+
+        let mut roots = vec![UNKNOWN; self.link_parent.len()];
+        let mut path = vec![];
+        for key in 0..self.link_parent.len() {
+            let mut k = key;
+            while roots[k] == UNKNOWN && self.link_parent[k].index() != k {
+                path.push(k);
+                k = self.link_parent[k].index();
+            }
+            let root = if roots[k] == UNKNOWN { k } else { roots[k] };
+            roots[k] = root;
+            for k in path.drain(..) {
+                roots[k] = root;
+            }
+        }
+        roots
+    }
+
+    /// Returns the height of the tree of each root, which is `0` for the
+    /// other nodes.
+    ///
+    /// The depths and the roots of the nodes are memoized, so this takes O(n)
+    /// time in total.
+    fn rank_table(&self) -> Vec<u8> {
+        const UNKNOWN: usize = usize::MAX;
+
+        let len = self.link_parent.len();
+        let mut depth = vec![UNKNOWN; len];
+        let mut roots = vec![UNKNOWN; len];
+        let mut rank = vec![0; len];
+        let mut path = vec![];
+        for node in 0..len {
+            let mut k = node;
+            while depth[k] == UNKNOWN && self.link_parent[k].index() != k {
+                path.push(k);
+                k = self.link_parent[k].index();
+            }
+            if depth[k] == UNKNOWN {
+                depth[k] = 0;
+                roots[k] = k;
+            }
+            let (mut d, root) = (depth[k], roots[k]);
+            while let Some(k) = path.pop() {
+                d += 1;
+                depth[k] = d;
+                roots[k] = root;
+            }
+            rank[root] = rank[root].max(u8::try_from(d).unwrap_or(u8::MAX));
+        }
+        rank
+    }
+
+    /// Returns the parent link and the value of each key, without the
+    /// placeholders left by `isolate`.
+    ///
+    /// If `isolate` has been called, every key links directly to the
+    /// identifier of its set, so the links are the same as if the sets had
+    /// been built without `isolate`.
+    fn flat_links(&self) -> Vec<(usize, Option<&V>)> {
+        if self.link_node.is_empty() {
+            return self
+                .link_parent
+                .iter()
+                .zip(&self.payload)
+                .map(|(parent, data)| (parent.index(), data.as_ref()))
+                .collect();
+        }
+        let roots = self.root_table();
+        (0..self.size())
+            .map(|key| {
+                let node = self.node_of(K::from_index(key));
+                let parent = self.owner_of(roots[node]).index();
+                (parent, self.payload[node].as_ref())
+            })
+            .collect()
     }
 }
 
-impl<A: Union> FromIterator<A> for QuickUnionUf<A> {
+impl<V: Codec, K: Idx> QuickUnionUf<V, K> {
+    /// Writes `self` in the binary snapshot format.
+    ///
+    /// All integers are little-endian. The snapshot consists of:
+    ///
+    /// 1. A 40-byte header: the magic bytes `UNIONFND`, the format version
+    ///    (`u32`, currently `1`), the flags (`u32`, currently `0`), the
+    ///    number of keys `n` (`u64`), the number of sets (`u64`) and the size
+    ///    of an encoded value `Codec::SIZE` (`u64`).
+    /// 2. The parent array: `n` parent keys as `u64`. A key is a root if it
+    ///    is its own parent.
+    /// 3. The payload array: `n` values of `Codec::SIZE` bytes. The values
+    ///    of non-root keys are filled with zeros.
+    ///
+    /// The snapshot can be loaded by `read_from`, or queried in place by
+    /// `QuickUnionView`. `writer` should be buffered, since this writes each
+    /// element separately.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let links = self.flat_links();
+        let header = Header {
+            len: links.len(),
+            set_count: self.set_count,
+        };
+        header.write_to(V::SIZE, &mut writer)?;
+
+        for &(parent, _) in &links {
+            writer.write_all(&(parent as u64).to_le_bytes())?;
+        }
+        let mut buf = vec![0; V::SIZE];
+        for &(_, data) in &links {
+            buf.fill(0);
+            if let Some(data) = data {
+                data.encode(&mut buf);
+            }
+            writer.write_all(&buf)?;
+        }
+        Ok(())
+    }
+
+    /// Reads `self` from the binary snapshot format written by `write_to`.
+    ///
+    /// Returns an error of kind `InvalidData` if the snapshot is malformed,
+    /// for example if the parent links are out of range or form a cycle.
+    /// `reader` should be buffered, since this reads each element
+    /// separately.
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<QuickUnionUf<V, K>> {
+        let header = Header::read_from(V::SIZE, &mut reader)?;
+        let len = header.len;
+
+        // The capacity is not reserved from the untrusted header.
+        let mut link_parent = vec![];
+        let mut buf = [0; 8];
+        for _ in 0..len {
+            reader.read_exact(&mut buf)?;
+            let parent = binary::decode_size(&buf)
+                .ok_or_else(|| binary::invalid_data("parent link out of range"))?;
+            link_parent.push(parent);
+        }
+        util::check_forest(len, |k| link_parent[k]).map_err(binary::invalid_data)?;
+
+        let mut payload = vec![];
+        let mut set_count = 0;
+        let mut buf = vec![0; V::SIZE];
+        for (key, &parent) in link_parent.iter().enumerate() {
+            reader.read_exact(&mut buf)?;
+            if parent == key {
+                let data =
+                    V::decode(&buf).ok_or_else(|| binary::invalid_data("invalid payload"))?;
+                payload.push(Some(data));
+                set_count += 1;
+            } else {
+                payload.push(None);
+            }
+        }
+        if set_count != header.set_count {
+            return Err(binary::invalid_data("set count mismatch"));
+        }
+
+        Ok(QuickUnionUf {
+            link_parent: link_parent.into_iter().map(K::from_index).collect(),
+            payload,
+            rank: vec![],
+            set_count,
+            link_node: vec![],
+            link_owner: vec![],
+            link_next: vec![],
+        })
+    }
+}
+
+impl<V: Detach, K: Idx> QuickUnionUf<V, K> {
+    /// Moves the element of the key out of its set into a new singleton set
+    /// whose value is `data`.
+    ///
+    /// `data` is the value of the element itself, so it is also removed from
+    /// the value of the former set by `Detach::detach`. As other
+    /// keys may have been linked under the key, the key is redirected to a
+    /// fresh node instead of being unlinked, and its old node is left in the
+    /// former set as a placeholder. The first call allocates the tables of
+    /// the redirections and takes O(n) time. After that, this takes the same
+    /// amortized time as `find`, and adds at most one node. Once the
+    /// placeholders outnumber the keys, the nodes are rebuilt without them
+    /// in O(n) time, so the memory stays proportional to the number of keys.
+    ///
+    /// ```
+    /// use union_find::{QuickUnionUf, UnionBySize, UnionFind};
+    ///
+    /// let mut uf = QuickUnionUf::<UnionBySize>::new(3);
+    /// assert!(uf.union(0, 1));
+    /// assert!(uf.union(1, 2));
+    ///
+    /// uf.isolate(0, UnionBySize::default());
+    /// assert!(!uf.connected(0, 1));
+    /// assert_eq!(uf.get(1).size(), 2);
+    /// assert_eq!(uf.get(0).size(), 1);
+    /// assert_eq!(uf.set_count(), 2);
+    /// ```
     #[inline]
-    fn from_iter<T: IntoIterator<Item = A>>(iterator: T) -> QuickUnionUf<A> {
+    pub fn isolate(&mut self, key: K, data: V) {
+        if self.link_node.is_empty() {
+            self.redirect();
+        }
+
+        let mut node = self.node_of(key);
+        let root = self.find_root(node);
+        if node == root {
+            let Some(other) = self.next_owned(root) else {
+                // The set only contains the key.
+                self.payload[root] = Some(data);
+                return;
+            };
+            // Hand the root over to another key, so that the identifier of
+            // the former set stays one of its keys.
+            let other_key = self.owner_of(other);
+            self.link_node[other_key.index()] = K::from_index(root);
+            self.link_owner[root] = other_key;
+            self.link_node[key.index()] = K::from_index(other);
+            self.link_owner[other] = key;
+            node = other;
+        }
+        debug_assert_ne!(node, root);
+
+        let set = self.payload[root].take().unwrap();
+        self.payload[root] = Some(Detach::detach(set, &data));
+
+        let fresh = K::from_index(self.link_parent.len());
+        self.link_parent.push(fresh);
+        self.payload.push(Some(data));
+        if !self.rank.is_empty() {
+            self.rank.push(0);
+        }
+        self.link_owner.push(key);
+        self.link_next.push(fresh);
+        self.link_node[key.index()] = fresh;
+        self.set_count += 1;
+
+        if self.link_parent.len() > 2 * self.link_node.len() {
+            self.compact();
+        }
+    }
+
+    /// Rebuilds the nodes without the placeholders, so that each key is its
+    /// own node again and links directly to the identifier of its set.
+    fn compact(&mut self) {
+        let roots = self.root_table();
+        let len = self.link_node.len();
+        let mut link_parent = Vec::with_capacity(len);
+        let mut payload = Vec::with_capacity(len);
+        for key in 0..len {
+            let node = self.link_node[key].index();
+            link_parent.push(self.link_owner[roots[node]]);
+            payload.push(self.payload[node].take());
+        }
+
+        self.link_parent = link_parent;
+        self.payload = payload;
+        self.link_node = vec![];
+        self.link_owner = vec![];
+        self.link_next = vec![];
+        if !self.rank.is_empty() {
+            self.rank = self.rank_table();
+        }
+    }
+
+    /// Fills the tables of the redirections, where each key is its own
+    /// node.
+    fn redirect(&mut self) {
+        let len = self.link_parent.len();
+        self.link_node = (0..len).map(K::from_index).collect();
+        self.link_owner = self.link_node.clone();
+        self.link_next = self.link_node.clone();
+        for (node, root) in self.root_table().into_iter().enumerate() {
+            if node != root {
+                self.link_next.swap(node, root);
+            }
+        }
+    }
+
+    /// Returns a node other than `root` in the set of `root` that is not a
+    /// placeholder, or `None` if there is no such node.
+    ///
+    /// The placeholders that are passed are removed from the list of the
+    /// set, so that each of them is passed at most once.
+    fn next_owned(&mut self, root: usize) -> Option<usize> {
+        loop {
+            let next = self.link_next[root].index();
+            if next == root {
+                return None;
+            }
+            if self.is_owned(next) {
+                return Some(next);
+            }
+            self.link_next[root] = self.link_next[next];
+        }
+    }
+}
+
+impl<A, K: Idx> FromIterator<A> for QuickUnionUf<A, K> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = A>>(iterator: T) -> QuickUnionUf<A, K> {
         let mut uf = QuickUnionUf {
             link_parent: vec![],
             payload: vec![],
+            rank: vec![],
+            set_count: 0,
+            link_node: vec![],
+            link_owner: vec![],
+            link_next: vec![],
         };
         uf.extend(iterator);
         uf
     }
 }
 
-impl<A> Extend<A> for QuickUnionUf<A> {
+impl<A, K: Idx> Extend<A> for QuickUnionUf<A, K> {
     #[inline]
     fn extend<T>(&mut self, iterable: T)
     where
         T: IntoIterator<Item = A>,
     {
+        if !self.link_node.is_empty() {
+            for data in iterable {
+                let _ = self.insert(data);
+            }
+            return;
+        }
+
         let len = self.payload.len();
         let payload = iterable.into_iter().map(Some);
         self.payload.extend(payload);
 
         let new_len = self.payload.len();
-        self.link_parent.extend(len..new_len);
+        self.link_parent.extend((len..new_len).map(K::from_index));
+        if !self.rank.is_empty() {
+            self.rank.resize(new_len, 0);
+        }
+        self.set_count += new_len - len;
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct QuickUnionRef<L, P> {
+    link_parent: L,
+    payload: P,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct QuickUnionRepr<V> {
+    link_parent: Vec<usize>,
+    payload: Vec<Option<V>>,
+}
+
+#[cfg(feature = "serde")]
+impl<V: Serialize, K: Idx> Serialize for QuickUnionUf<V, K> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.link_node.is_empty() {
+            return QuickUnionRef {
+                link_parent: util::Indices(&self.link_parent),
+                payload: &self.payload,
+            }
+            .serialize(serializer);
+        }
+        let (link_parent, payload): (Vec<_>, Vec<_>) = self.flat_links().into_iter().unzip();
+        QuickUnionRef {
+            link_parent,
+            payload,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, V: Deserialize<'de>, K: Idx> Deserialize<'de> for QuickUnionUf<V, K> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = QuickUnionRepr::<V>::deserialize(deserializer)?;
+        if repr.link_parent.len() != repr.payload.len() {
+            return Err(de::Error::custom(
+                "`link_parent` and `payload` have different lengths",
+            ));
+        }
+        util::check_forest(repr.link_parent.len(), |k| repr.link_parent[k])
+            .map_err(de::Error::custom)?;
+
+        let mut set_count = 0;
+        for (key, (&parent, payload)) in repr.link_parent.iter().zip(&repr.payload).enumerate() {
+            match (parent == key, payload.is_some()) {
+                (true, true) => set_count += 1,
+                (true, false) => return Err(de::Error::custom("root without payload")),
+                (false, true) => return Err(de::Error::custom("payload on non-root key")),
+                (false, false) => {}
+            }
+        }
+
+        Ok(QuickUnionUf {
+            link_parent: repr.link_parent.into_iter().map(K::from_index).collect(),
+            payload: repr.payload,
+            rank: vec![],
+            set_count,
+            link_node: vec![],
+            link_owner: vec![],
+            link_next: vec![],
+        })
     }
 }
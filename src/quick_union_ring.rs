@@ -0,0 +1,135 @@
+// Copyright 2016 union-find-rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::{Idx, Members, QuickUnionUf, Union, UnionFind, UnionOutcome};
+use std::iter::FromIterator;
+
+/// Union-Find implementation with quick union operation, which also keeps
+/// track of the members of each set.
+///
+/// In addition to the links of `QuickUnionUf`, every key has a link to the
+/// next member of its set, forming a circular list per set. The lists of two
+/// sets are spliced together in O(1) by `union`, so `members` can enumerate a
+/// set in time proportional to its size.
+#[derive(Debug)]
+pub struct QuickUnionRingUf<V, K = usize> {
+    inner: QuickUnionUf<V, K>,
+    link_next: Vec<K>,
+}
+
+impl<V: Clone, K: Clone> Clone for QuickUnionRingUf<V, K> {
+    #[inline]
+    fn clone(&self) -> QuickUnionRingUf<V, K> {
+        QuickUnionRingUf {
+            inner: self.inner.clone(),
+            link_next: self.link_next.clone(),
+        }
+    }
+
+    #[inline]
+    fn clone_from(&mut self, other: &QuickUnionRingUf<V, K>) {
+        self.inner.clone_from(&other.inner);
+        self.link_next.clone_from(&other.link_next);
+    }
+}
+
+impl<V: Union, K: Idx> UnionFind<V, K> for QuickUnionRingUf<V, K> {
+    #[inline]
+    fn size(&self) -> usize {
+        self.inner.size()
+    }
+
+    #[inline]
+    fn set_count(&self) -> usize {
+        self.inner.set_count()
+    }
+
+    #[inline]
+    fn insert(&mut self, data: V) -> K {
+        let key = self.inner.insert(data);
+        self.link_next.push(key);
+        key
+    }
+
+    #[inline]
+    fn union(&mut self, key0: K, key1: K) -> bool {
+        self.union_detailed(key0, key1).is_merged()
+    }
+
+    #[inline]
+    fn union_detailed(&mut self, key0: K, key1: K) -> UnionOutcome<K> {
+        let outcome = self.inner.union_detailed(key0, key1);
+        if let UnionOutcome::Merged { root, absorbed } = outcome {
+            // Swapping the links of two elements in different rings splices
+            // the rings into one.
+            self.link_next.swap(root.index(), absorbed.index());
+        }
+        outcome
+    }
+
+    #[inline]
+    fn find(&mut self, key: K) -> K {
+        self.inner.find(key)
+    }
+
+    #[inline]
+    fn get(&mut self, key: K) -> &V {
+        self.inner.get(key)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: K) -> &mut V {
+        self.inner.get_mut(key)
+    }
+
+    #[inline]
+    fn find_immutable(&self, key: K) -> K {
+        self.inner.find_immutable(key)
+    }
+
+    #[inline]
+    fn get_immutable(&self, key: K) -> &V {
+        self.inner.get_immutable(key)
+    }
+}
+
+impl<V, K: Idx> QuickUnionRingUf<V, K> {
+    /// Returns an iterator over the keys of the set that the key belongs to.
+    ///
+    /// This walks the circular list of the set, so it takes time proportional
+    /// to the size of the set rather than the size of `self`.
+    #[inline]
+    pub fn members(&self, key: K) -> Members<'_, K> {
+        Members::new(&self.link_next, self.link_next[key.index()], key)
+    }
+}
+
+impl<A: Union, K: Idx> FromIterator<A> for QuickUnionRingUf<A, K> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = A>>(iterator: T) -> QuickUnionRingUf<A, K> {
+        let mut uf = QuickUnionRingUf {
+            inner: QuickUnionUf::from_iter(None),
+            link_next: vec![],
+        };
+        uf.extend(iterator);
+        uf
+    }
+}
+
+impl<A, K: Idx> Extend<A> for QuickUnionRingUf<A, K> {
+    #[inline]
+    fn extend<T>(&mut self, iterable: T)
+    where
+        T: IntoIterator<Item = A>,
+    {
+        let len = self.link_next.len();
+        let mut new_len = len;
+        self.inner
+            .extend(iterable.into_iter().inspect(|_| new_len += 1));
+        self.link_next.extend((len..new_len).map(K::from_index));
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright 2016 union-find-rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::{Union, UnionFind, UnionOutcome, UnionResult};
+use std::iter::FromIterator;
+
+#[derive(Copy, Clone, Debug)]
+struct Record<V> {
+    parent: usize,
+    child: usize,
+    parent_data: V,
+    child_data: V,
+    serial: u64,
+}
+
+/// A point in the history of `RollbackUf`, created by `snapshot`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Checkpoint {
+    history_len: usize,
+    // The serial number of the last recorded union, which tells whether the
+    // history has been rolled back past the checkpoint and recorded again.
+    last_serial: Option<u64>,
+}
+
+/// Union-Find implementation that can undo `union` operations.
+///
+/// Every successful `union` is recorded on a history stack together with the
+/// values of both sets before the union, and `rollback` undoes them in LIFO
+/// order. Paths are never compressed so that undoing a union only needs to
+/// restore the two roots, which makes `find` take O(log n) time when the
+/// sets are joined by size or rank.
+///
+/// Inserted sets are not recorded, and are kept by `rollback`.
+///
+/// ```
+/// use union_find::{RollbackUf, UnionBySize, UnionFind};
+///
+/// let mut uf = RollbackUf::<UnionBySize>::new(4);
+/// assert!(uf.union(0, 1));
+///
+/// let checkpoint = uf.snapshot();
+/// assert!(uf.union(1, 2));
+/// assert!(uf.union(2, 3));
+/// assert_eq!(uf.get(0).size(), 4);
+///
+/// uf.rollback_to(checkpoint);
+/// assert!(uf.connected(0, 1));
+/// assert!(!uf.connected(1, 2));
+/// assert_eq!(uf.get(0).size(), 2);
+/// ```
+#[derive(Debug)]
+pub struct RollbackUf<V> {
+    link_parent: Vec<usize>,
+    payload: Vec<Option<V>>,
+    history: Vec<Record<V>>,
+    next_serial: u64,
+}
+
+impl<V: Clone> Clone for RollbackUf<V> {
+    #[inline]
+    fn clone(&self) -> RollbackUf<V> {
+        RollbackUf {
+            link_parent: self.link_parent.clone(),
+            payload: self.payload.clone(),
+            history: self.history.clone(),
+            next_serial: self.next_serial,
+        }
+    }
+
+    #[inline]
+    fn clone_from(&mut self, other: &RollbackUf<V>) {
+        self.link_parent.clone_from(&other.link_parent);
+        self.payload.clone_from(&other.payload);
+        self.history.clone_from(&other.history);
+        self.next_serial = other.next_serial;
+    }
+}
+
+impl<V: Union + Clone> UnionFind<V> for RollbackUf<V> {
+    #[inline]
+    fn size(&self) -> usize {
+        self.payload.len()
+    }
+
+    #[inline]
+    fn set_count(&self) -> usize {
+        // Every recorded union has joined two sets into one.
+        self.payload.len() - self.history.len()
+    }
+
+    #[inline]
+    fn insert(&mut self, data: V) -> usize {
+        let key = self.payload.len();
+        self.link_parent.push(key);
+        self.payload.push(Some(data));
+        key
+    }
+
+    #[inline]
+    fn union(&mut self, key0: usize, key1: usize) -> bool {
+        self.union_detailed(key0, key1).is_merged()
+    }
+
+    #[inline]
+    fn union_detailed(&mut self, key0: usize, key1: usize) -> UnionOutcome<usize> {
+        let k0 = self.find(key0);
+        let k1 = self.find(key1);
+        if k0 == k1 {
+            return UnionOutcome::AlreadyJoined { root: k0 };
+        }
+
+        // Temporary replace with dummy to move out the elements of the vector.
+        let v0 = self.payload[k0].take().unwrap();
+        let v1 = self.payload[k1].take().unwrap();
+
+        let (parent, child, val, parent_data, child_data) =
+            match Union::union(v0.clone(), v1.clone()) {
+                UnionResult::Left(val) => (k0, k1, val, v0, v1),
+                UnionResult::Right(val) => (k1, k0, val, v1, v0),
+            };
+        self.payload[parent] = Some(val);
+        self.link_parent[child] = parent;
+
+        self.history.push(Record {
+            parent,
+            child,
+            parent_data,
+            child_data,
+            serial: self.next_serial,
+        });
+        self.next_serial += 1;
+
+        UnionOutcome::Merged {
+            root: parent,
+            absorbed: child,
+        }
+    }
+
+    #[inline]
+    fn find(&mut self, key: usize) -> usize {
+        self.find_immutable(key)
+    }
+
+    #[inline]
+    fn get(&mut self, key: usize) -> &V {
+        self.get_immutable(key)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: usize) -> &mut V {
+        let root_key = self.find(key);
+        self.payload[root_key].as_mut().unwrap()
+    }
+
+    #[inline]
+    fn find_immutable(&self, key: usize) -> usize {
+        let mut k = key;
+        let mut p = self.link_parent[k];
+        while p != k {
+            k = p;
+            p = self.link_parent[k];
+        }
+        k
+    }
+
+    #[inline]
+    fn get_immutable(&self, key: usize) -> &V {
+        let root_key = self.find_immutable(key);
+        self.payload[root_key].as_ref().unwrap()
+    }
+}
+
+impl<V> RollbackUf<V> {
+    /// Returns a checkpoint of the current state of `self`.
+    #[inline]
+    pub fn snapshot(&self) -> Checkpoint {
+        Checkpoint {
+            history_len: self.history.len(),
+            last_serial: self.history.last().map(|record| record.serial),
+        }
+    }
+
+    /// Undoes the last `union` operation.
+    ///
+    /// Returns `false` if there is no `union` operation to undo.
+    #[inline]
+    pub fn rollback(&mut self) -> bool {
+        let Some(record) = self.history.pop() else {
+            return false;
+        };
+        self.link_parent[record.child] = record.child;
+        self.payload[record.parent] = Some(record.parent_data);
+        self.payload[record.child] = Some(record.child_data);
+        true
+    }
+
+    /// Undoes all `union` operations performed after the checkpoint.
+    ///
+    /// This takes time proportional to the number of undone operations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the checkpoint has already been rolled back past, even if
+    /// other `union` operations have been recorded since then.
+    #[inline]
+    pub fn rollback_to(&mut self, checkpoint: Checkpoint) {
+        let last_serial = self
+            .history
+            .get(..checkpoint.history_len)
+            .map(|history| history.last().map(|record| record.serial));
+        assert!(
+            last_serial == Some(checkpoint.last_serial),
+            "checkpoint has already been rolled back past"
+        );
+        while self.history.len() > checkpoint.history_len {
+            let _ = self.rollback();
+        }
+    }
+}
+
+impl<A: Union> FromIterator<A> for RollbackUf<A> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = A>>(iterator: T) -> RollbackUf<A> {
+        let mut uf = RollbackUf {
+            link_parent: vec![],
+            payload: vec![],
+            history: vec![],
+            next_serial: 0,
+        };
+        uf.extend(iterator);
+        uf
+    }
+}
+
+impl<A> Extend<A> for RollbackUf<A> {
+    #[inline]
+    fn extend<T>(&mut self, iterable: T)
+    where
+        T: IntoIterator<Item = A>,
+    {
+        let len = self.payload.len();
+        let payload = iterable.into_iter().map(Some);
+        self.payload.extend(payload);
+
+        let new_len = self.payload.len();
+        self.link_parent.extend(len..new_len);
+    }
+}
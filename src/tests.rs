@@ -38,15 +38,1367 @@ where
     assert_eq!(4, uf.get(100).size());
 }
 
+pub fn set_count<T>()
+where
+    T: UnionFind<UnionBySize>,
+{
+    let mut uf = T::new(100);
+    assert_eq!(100, uf.set_count());
+    assert!(uf.union(0, 1));
+    assert!(uf.union(1, 2));
+    assert!(!uf.union(0, 2));
+    assert_eq!(98, uf.set_count());
+    let k100 = uf.insert(UnionBySize::default());
+    assert_eq!(99, uf.set_count());
+    assert!(uf.union(k100, 0));
+    assert_eq!(98, uf.set_count());
+    uf.extend((0..3).map(|_| UnionBySize::default()));
+    assert_eq!(101, uf.set_count());
+}
+
+pub fn find_immutable<T>()
+where
+    T: UnionFind<UnionBySize>,
+{
+    let mut uf = T::new(10);
+    assert!(uf.union(0, 1));
+    assert!(uf.union(2, 3));
+    assert!(uf.union(1, 3));
+    assert!(uf.union(5, 6));
+
+    let uf = &uf;
+    for i in 0..10 {
+        assert_eq!(
+            uf.find_immutable(i),
+            uf.find_immutable(uf.find_immutable(i))
+        );
+    }
+    assert!(uf.connected_immutable(0, 2));
+    assert!(uf.connected_immutable(3, 0));
+    assert!(uf.connected_immutable(5, 6));
+    assert!(!uf.connected_immutable(0, 5));
+    assert!(!uf.connected_immutable(4, 7));
+    assert_eq!(4, uf.get_immutable(3).size());
+    assert_eq!(2, uf.get_immutable(6).size());
+    assert_eq!(1, uf.get_immutable(9).size());
+}
+
+pub fn connected<T>()
+where
+    T: UnionFind<UnionBySize>,
+{
+    let mut uf = T::new(10);
+    assert!(uf.connected(0, 0));
+    assert!(!uf.connected(0, 1));
+    assert!(uf.union(0, 1));
+    assert!(uf.union(1, 2));
+    assert!(uf.union(7, 8));
+    assert!(uf.connected(0, 2));
+    assert!(uf.connected(2, 0));
+    assert!(!uf.connected(2, 7));
+
+    let pairs = [(0, 1), (0, 2), (1, 7), (8, 7), (9, 9), (3, 4)];
+    assert_eq!(
+        uf.connected_many(&pairs),
+        [true, true, false, true, true, false]
+    );
+    assert!(uf.connected_many(&[]).is_empty());
+}
+
+pub fn typed_keys<T>()
+where
+    T: UnionFind<UnionBySize, u32>,
+{
+    let mut uf = T::new(4);
+    let k4: u32 = uf.insert(UnionBySize::default());
+    assert_eq!(k4, 4);
+    assert!(uf.union(0, k4));
+    assert!(uf.union(2, 3));
+    assert!(uf.connected(4, 0));
+    assert!(!uf.connected(4, 3));
+    assert_eq!(2, uf.get(k4).size());
+    assert_eq!(3, uf.set_count());
+}
+
+#[cfg(feature = "rayon")]
+pub fn union_all_par<T>()
+where
+    T: UnionFind<UnionBySize>,
+{
+    const LEN: usize = 5000;
+
+    let pairs = edges(LEN, 4000);
+    let (before, after) = pairs.split_at(1000);
+
+    let mut seq = T::new(LEN);
+    let mut par = T::new(LEN);
+    for &(a, b) in before {
+        let _ = seq.union(a, b);
+        let _ = par.union(a, b);
+    }
+    for &(a, b) in after {
+        let _ = seq.union(a, b);
+    }
+    par.union_all_par(after);
+
+    assert_eq!(seq.set_count(), par.set_count());
+    for key in 0..LEN {
+        assert_eq!(seq.get(key).size(), par.get(key).size());
+        let root = seq.find(key);
+        assert!(par.connected(key, root));
+    }
+}
+
+pub fn isolate<T, F>(mut isolate: F)
+where
+    T: UnionFind<UnionBySize>,
+    F: FnMut(&mut T, usize),
+{
+    let mut uf = T::new(100);
+    for i in 0..99 {
+        let _ = uf.union(i, i + 1);
+    }
+    let mut isolated = vec![];
+    for i in (0..100).step_by(3).chain([1, 97]) {
+        isolate(&mut uf, i);
+        assert_eq!(1, uf.get(i).size());
+        isolated.push(i);
+    }
+    assert_eq!(1 + isolated.len(), uf.set_count());
+    assert_eq!(100 - isolated.len(), uf.get(2).size());
+    for &key in &isolated {
+        assert!(!uf.connected(key, 2));
+        assert_eq!(key, uf.find(key));
+    }
+    assert!(!isolated.contains(&uf.find(2)));
+    for i in (0..100).filter(|i| i % 3 != 0 && ![1, 97].contains(i)) {
+        assert!(uf.connected(i, 2));
+    }
+
+    assert!(uf.union(isolated[0], isolated[1]));
+    assert_eq!(2, uf.get(isolated[1]).size());
+}
+
+#[cfg(feature = "serde")]
+pub fn serde_round_trip<T>()
+where
+    T: UnionFind<UnionBySize> + serde::Serialize + serde::de::DeserializeOwned,
+{
+    let mut uf = T::new(500);
+    for (a, b) in edges(500, 300) {
+        let _ = uf.union(a, b);
+    }
+
+    let json = serde_json::to_string(&uf).unwrap();
+    let mut loaded = serde_json::from_str::<T>(&json).unwrap();
+    assert_eq!(uf.set_count(), loaded.set_count());
+    for key in 0..500 {
+        assert_eq!(uf.find(key), loaded.find(key));
+        assert_eq!(uf.get(key).size(), loaded.get(key).size());
+    }
+    assert!(loaded.union(0, 499) || loaded.connected(0, 499));
+}
+
+pub fn try_api<T: UnionFind<UnionBySize>>() {
+    let out_of_range = crate::UfError::KeyOutOfRange { key: 10, size: 10 };
+
+    let mut uf = T::new(10);
+    assert_eq!(Ok(true), uf.try_union(0, 1));
+    assert_eq!(Ok(false), uf.try_union(1, 0));
+    assert_eq!(Err(out_of_range), uf.try_union(0, 10));
+    assert_eq!(Err(out_of_range), uf.try_union(10, 0));
+    assert_eq!(9, uf.set_count());
+
+    assert_eq!(Ok(uf.find(1)), uf.try_find(1));
+    assert_eq!(Err(out_of_range), uf.try_find(10));
+    assert_eq!(2, uf.try_get(1).unwrap().size());
+    assert_eq!(Err(out_of_range), uf.try_get(10).map(|v| v.size()));
+    assert!(uf.try_get_mut(9).is_ok());
+    assert!(uf.try_get_mut(usize::MAX).is_err());
+
+    assert_eq!(
+        "key 10 is out of range for size 10",
+        out_of_range.to_string()
+    );
+}
+
+pub fn union_detailed<V, T>()
+where
+    V: crate::Union + Default,
+    T: UnionFind<V>,
+{
+    let mut uf = T::new(100);
+    for (a, b) in edges(100, 150) {
+        let (ra, rb) = (uf.find(a), uf.find(b));
+        match uf.union_detailed(a, b) {
+            crate::UnionOutcome::Merged { root, absorbed } => {
+                assert!((root, absorbed) == (ra, rb) || (root, absorbed) == (rb, ra));
+                assert_eq!(root, uf.find(a));
+                assert_eq!(root, uf.find(absorbed));
+            }
+            crate::UnionOutcome::AlreadyJoined { root } => {
+                assert_eq!(ra, rb);
+                assert_eq!(ra, root);
+            }
+        }
+    }
+}
+
+pub fn union_with<T, F>(mut union_with: F)
+where
+    T: UnionFind<Vec<usize>>,
+    F: FnMut(&mut T, usize, usize) -> bool,
+{
+    const LEN: usize = 300;
+
+    let mut uf = (0..LEN).map(|i| vec![i]).collect::<T>();
+    let mut quick = crate::QuickUnionUf::<UnionBySize>::new(LEN);
+    for (a, b) in edges(LEN, 250) {
+        assert_eq!(quick.union(a, b), union_with(&mut uf, a, b));
+    }
+    assert_eq!(quick.set_count(), uf.set_count());
+    for key in 0..LEN {
+        let members = uf.get(key).clone();
+        assert_eq!(quick.get(key).size(), members.len());
+        assert!(members.iter().all(|&k| quick.connected(k, key)));
+    }
+}
+
+fn sorted_sets<'a, I>(sets: I) -> Vec<(usize, usize, Vec<usize>)>
+where
+    I: Iterator<Item = (usize, &'a UnionBySize, Vec<usize>)>,
+{
+    sets.map(|(root, v, mut members)| {
+        members.sort();
+        (root, v.size(), members)
+    })
+    .collect()
+}
+
+/// Returns `count` pseudo-random pairs of keys below `len`.
+fn edges(len: usize, count: usize) -> Vec<(usize, usize)> {
+    let mut x = 1u64;
+    (0..count)
+        .map(|_| {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((x >> 33) as usize % len, (x >> 13) as usize % len)
+        })
+        .collect()
+}
+
 mod quick_union {
+    use crate::UnionFind;
+
+    #[test]
+    fn isolate() {
+        super::isolate::<crate::QuickUnionUf<crate::UnionBySize>, _>(|uf, key| {
+            uf.isolate(key, Default::default())
+        });
+
+        let mut uf = crate::QuickUnionUf::<crate::UnionBySize>::new(4);
+        let _ = uf.union(0, 1);
+        let _ = uf.union(2, 3);
+        uf.isolate(2, Default::default());
+        let sets = uf.sets().map(|(_, _, members)| members).collect::<Vec<_>>();
+        assert_eq!(sets, [vec![0, 1], vec![2], vec![3]]);
+
+        // The former set is dropped once all of its keys have been isolated.
+        let mut uf = crate::QuickUnionUf::<crate::UnionBySize>::new(2);
+        let _ = uf.union(0, 1);
+        uf.isolate(0, Default::default());
+        uf.isolate(1, Default::default());
+        assert_eq!(2, uf.set_count());
+        assert_eq!(2, uf.roots().count());
+        assert_eq!(vec![0, 1], uf.canonical_labels());
+        assert!(!uf.connected(0, 1));
+        assert!(uf.union(0, 1));
+        assert_eq!(2, uf.get(1).size());
+        assert_eq!(1, uf.set_count());
+    }
+
+    #[test]
+    fn isolate_repeatedly() {
+        let mut uf = crate::QuickUnionUf::<crate::UnionBySize>::new(4);
+        for i in 0..1000 {
+            for key in 0..3 {
+                let _ = uf.union(key, key + 1);
+            }
+            uf.isolate(i % 4, Default::default());
+            assert_eq!(4, uf.size());
+            assert_eq!(2, uf.set_count());
+            assert_eq!(1, uf.get(i % 4).size());
+            assert_eq!(3, uf.get((i + 1) % 4).size());
+            assert!(uf.node_count() <= 8);
+        }
+    }
+
     #[test]
     fn union_find() {
         super::union_find::<crate::QuickUnionUf<crate::UnionBySize>>();
     }
+
+    #[test]
+    fn set_count() {
+        super::set_count::<crate::QuickUnionUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn find_immutable() {
+        super::find_immutable::<crate::QuickUnionUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn connected() {
+        super::connected::<crate::QuickUnionUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn union_detailed() {
+        super::union_detailed::<_, crate::QuickUnionUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn typed_keys() {
+        super::typed_keys::<crate::QuickUnionUf<crate::UnionBySize, u32>>();
+    }
+
+    #[test]
+    fn try_api() {
+        super::try_api::<crate::QuickUnionUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn union_with() {
+        super::union_with::<crate::QuickUnionUf<Vec<usize>>, _>(|uf, a, b| {
+            uf.union_with(a, b, |mut l, r| {
+                l.extend(r);
+                l
+            })
+        });
+
+        // A set of higher rank stays the parent, whichever side it is on.
+        let mut uf: crate::QuickUnionUf<u32> = (0..100).collect();
+        assert!(uf.union_with(0, 1, |l, r| l + r));
+        for key in 2..100 {
+            assert!(uf.union_with(key, 0, |l, r| l + r));
+            assert_eq!(0, uf.find(key));
+        }
+        assert_eq!(&4950, uf.get(99));
+        let key = uf.insert(50);
+        assert!(uf.union_with(key, 99, |l, r| l + r));
+        assert_eq!(0, uf.find(key));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn union_all_par() {
+        super::union_all_par::<crate::QuickUnionUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn sets() {
+        let mut uf = crate::QuickUnionUf::<crate::UnionBySize>::new(6);
+        assert!(uf.union(0, 3));
+        assert!(uf.union(4, 3));
+        assert!(uf.union(1, 5));
+
+        let r0 = uf.find(0);
+        let r1 = uf.find(1);
+        let mut sets = super::sorted_sets(uf.sets());
+        sets.sort();
+        assert_eq!(
+            sets,
+            [(r0, 3, vec![0, 3, 4]), (r1, 2, vec![1, 5]), (2, 1, vec![2])]
+        );
+    }
+
+    #[test]
+    fn roots() {
+        let mut uf = crate::QuickUnionUf::<crate::UnionBySize>::new(6);
+        assert!(uf.union(0, 3));
+        assert!(uf.union(4, 3));
+        assert!(uf.union(1, 5));
+
+        let mut roots = uf.roots().collect::<Vec<_>>();
+        roots.sort();
+        let mut expected = vec![uf.find(0), uf.find(1), uf.find(2)];
+        expected.sort();
+        assert_eq!(roots, expected);
+        assert_eq!(uf.canonical_labels(), [0, 1, 2, 0, 0, 1]);
+    }
+}
+mod quick_union_ring {
+    use crate::UnionFind;
+
+    #[test]
+    fn union_find() {
+        super::union_find::<crate::QuickUnionRingUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn set_count() {
+        super::set_count::<crate::QuickUnionRingUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn find_immutable() {
+        super::find_immutable::<crate::QuickUnionRingUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn connected() {
+        super::connected::<crate::QuickUnionRingUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn union_detailed() {
+        super::union_detailed::<_, crate::QuickUnionRingUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn typed_keys() {
+        super::typed_keys::<crate::QuickUnionRingUf<crate::UnionBySize, u32>>();
+    }
+
+    #[test]
+    fn members() {
+        let mut uf = crate::QuickUnionRingUf::<crate::UnionBySize>::new(10);
+        assert_eq!(uf.members(3).collect::<Vec<_>>(), [3]);
+
+        assert!(uf.union(0, 1));
+        assert!(uf.union(2, 3));
+        assert!(uf.union(4, 3));
+        assert!(uf.union(1, 4));
+        assert!(!uf.union(0, 2));
+
+        for key in [0, 1, 2, 3, 4] {
+            let mut members = uf.members(key).collect::<Vec<_>>();
+            members.sort();
+            assert_eq!(members, [0, 1, 2, 3, 4]);
+        }
+        assert_eq!(uf.members(5).collect::<Vec<_>>(), [5]);
+
+        let k10 = uf.insert(Default::default());
+        assert!(uf.union(9, k10));
+        let mut members = uf.members(k10).collect::<Vec<_>>();
+        members.sort();
+        assert_eq!(members, [9, 10]);
+    }
+}
+mod compact_quick_union {
+    use crate::UnionFind;
+
+    #[test]
+    fn union_find() {
+        let mut uf = crate::CompactQuickUnionUf::new(100);
+        assert_eq!(100, uf.set_count());
+        assert_eq!(1, uf.get(0).size());
+        assert!(uf.find(0) != uf.find(1));
+        assert!(uf.union(0, 1));
+        assert!(uf.find(0) == uf.find(1));
+        assert!(!uf.union(1, 0));
+        assert!(uf.union(1, 2));
+        assert_eq!(3, uf.get(2).size());
+        assert_eq!(1, uf.get(3).size());
+        assert_eq!(98, uf.set_count());
+        let k100 = uf.insert(Default::default());
+        assert_eq!(k100, 100);
+        assert!(uf.union(k100, 0));
+        assert_eq!(4, uf.get(100).size());
+        assert_eq!(98, uf.set_count());
+
+        let uf = &uf;
+        assert_eq!(uf.find_immutable(100), uf.find_immutable(2));
+        assert!(uf.connected_immutable(0, 100));
+        assert!(!uf.connected_immutable(0, 3));
+        assert_eq!(4, uf.get_immutable(1).size());
+    }
+
+    #[test]
+    fn union_detailed() {
+        super::union_detailed::<_, crate::CompactQuickUnionUf>();
+    }
+
+    #[test]
+    #[should_panic(expected = "too many elements")]
+    fn size_overflow() {
+        let mut size = crate::CompactSize::default();
+        for _ in 0..32 {
+            size = match crate::Union::union(size, size) {
+                crate::UnionResult::Left(size) | crate::UnionResult::Right(size) => size,
+            };
+        }
+    }
+
+    #[test]
+    fn matches_quick_union() {
+        let mut compact = crate::CompactQuickUnionUf::new(1000);
+        let mut quick = crate::QuickUnionUf::<crate::UnionBySize>::new(1000);
+        for (a, b) in super::edges(1000, 800) {
+            assert_eq!(quick.union(a, b), compact.union(a, b));
+        }
+        assert_eq!(quick.set_count(), compact.set_count());
+        for key in 0..1000 {
+            assert_eq!(quick.get(key).size(), compact.get(key).size());
+            assert!(compact.connected(key, quick.find(key)));
+        }
+    }
 }
 mod quick_find {
+    use crate::UnionFind;
+
+    #[test]
+    fn isolate() {
+        super::isolate::<crate::QuickFindUf<crate::UnionBySize>, _>(|uf, key| {
+            uf.isolate(key, Default::default())
+        });
+
+        let mut uf = crate::QuickFindUf::<crate::UnionBySize>::new(4);
+        let _ = uf.union(0, 1);
+        let _ = uf.union(0, 2);
+        uf.isolate(2, Default::default());
+        let _ = uf.union(1, 3);
+        assert_eq!(vec![0, 1, 3], uf.members(3).collect::<Vec<_>>());
+        assert_eq!(vec![2], uf.members(2).collect::<Vec<_>>());
+
+        // The former set loses one element, whatever the new value is.
+        let big = *uf.get(0);
+        uf.isolate(1, big);
+        assert_eq!(2, uf.get(0).size());
+        assert_eq!(3, uf.get(1).size());
+    }
+
+    #[test]
+    fn split_by() {
+        let mut uf = crate::QuickFindUf::<crate::UnionBySize>::new(100);
+        for i in 0..99 {
+            let _ = uf.union(i, i + 1);
+        }
+        let (r0, r1) = uf
+            .split_by(10, |k| k % 3 == 0, |_| Default::default())
+            .unwrap();
+        assert_eq!(2, uf.set_count());
+        assert_eq!(34, uf.get(r0).size());
+        assert_eq!(66, uf.get(r1).size());
+        for k in 0..100 {
+            let root = if k % 3 == 0 { r0 } else { r1 };
+            assert_eq!(root, uf.find(k));
+        }
+        assert_eq!(
+            (0..100).step_by(3).collect::<Vec<_>>(),
+            uf.members(r0).collect::<Vec<_>>()
+        );
+
+        assert_eq!(None, uf.split_by(r0, |k| k > 1000, |_| Default::default()));
+        assert_eq!(2, uf.set_count());
+
+        // The split sets can be joined again.
+        assert!(uf.union(r0, r1));
+        assert_eq!(100, uf.members(5).count());
+        assert_eq!(100, uf.get(5).size());
+    }
+
     #[test]
     fn union_find() {
         super::union_find::<crate::QuickFindUf<crate::UnionBySize>>();
     }
+
+    #[test]
+    fn set_count() {
+        super::set_count::<crate::QuickFindUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn find_immutable() {
+        super::find_immutable::<crate::QuickFindUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn connected() {
+        super::connected::<crate::QuickFindUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn union_detailed() {
+        super::union_detailed::<_, crate::QuickFindUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn typed_keys() {
+        super::typed_keys::<crate::QuickFindUf<crate::UnionBySize, u32>>();
+    }
+
+    #[test]
+    fn try_api() {
+        super::try_api::<crate::QuickFindUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn union_with() {
+        super::union_with::<crate::QuickFindUf<Vec<usize>>, _>(|uf, a, b| {
+            uf.union_with(a, b, |mut l, r| {
+                l.extend(r);
+                l
+            })
+        });
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn union_all_par() {
+        super::union_all_par::<crate::QuickFindUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn sets() {
+        let mut uf = crate::QuickFindUf::<crate::UnionBySize>::new(6);
+        assert!(uf.union(0, 3));
+        assert!(uf.union(4, 3));
+        assert!(uf.union(1, 5));
+
+        let r0 = uf.find(0);
+        let r1 = uf.find(1);
+        let mut sets = super::sorted_sets(uf.sets());
+        sets.sort();
+        assert_eq!(
+            sets,
+            [(r0, 3, vec![0, 3, 4]), (r1, 2, vec![1, 5]), (2, 1, vec![2])]
+        );
+    }
+
+    #[test]
+    fn roots() {
+        let mut uf = crate::QuickFindUf::<crate::UnionBySize>::new(6);
+        assert!(uf.union(0, 3));
+        assert!(uf.union(4, 3));
+        assert!(uf.union(1, 5));
+
+        let mut roots = uf.roots().collect::<Vec<_>>();
+        roots.sort();
+        let mut expected = vec![uf.find(0), uf.find(1), uf.find(2)];
+        expected.sort();
+        assert_eq!(roots, expected);
+        assert_eq!(uf.canonical_labels(), [0, 1, 2, 0, 0, 1]);
+    }
+
+    #[test]
+    fn members() {
+        let mut uf = crate::QuickFindUf::<crate::UnionBySize>::new(10);
+        assert_eq!(uf.members(3).collect::<Vec<_>>(), [3]);
+
+        assert!(uf.union(0, 1));
+        assert!(uf.union(2, 3));
+        assert!(uf.union(4, 3));
+        assert!(uf.union(1, 4));
+
+        for key in [0, 1, 2, 3, 4] {
+            let mut members = uf.members(key).collect::<Vec<_>>();
+            members.sort();
+            assert_eq!(members, [0, 1, 2, 3, 4]);
+        }
+        assert_eq!(uf.members(5).collect::<Vec<_>>(), [5]);
+        assert_eq!(uf.members(9).collect::<Vec<_>>(), [9]);
+    }
+}
+mod rollback {
+    use crate::UnionFind;
+
+    #[test]
+    fn union_find() {
+        super::union_find::<crate::RollbackUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn set_count() {
+        super::set_count::<crate::RollbackUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn find_immutable() {
+        super::find_immutable::<crate::RollbackUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn connected() {
+        super::connected::<crate::RollbackUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn union_detailed() {
+        super::union_detailed::<_, crate::RollbackUf<crate::UnionBySize>>();
+    }
+
+    #[test]
+    fn rollback() {
+        let mut uf = crate::RollbackUf::<crate::UnionBySize>::new(6);
+        assert!(!uf.rollback());
+
+        let c0 = uf.snapshot();
+        assert!(uf.union(0, 1));
+        assert!(uf.union(2, 3));
+        let c1 = uf.snapshot();
+        assert!(uf.union(1, 3));
+        assert!(!uf.union(0, 2));
+        assert!(uf.union(4, 5));
+        let c2 = uf.snapshot();
+        assert_eq!(2, uf.set_count());
+        assert_eq!(4, uf.get(2).size());
+
+        uf.rollback_to(c2);
+        assert_eq!(2, uf.set_count());
+
+        assert!(uf.rollback());
+        assert!(!uf.connected(4, 5));
+        assert_eq!(3, uf.set_count());
+
+        uf.rollback_to(c1);
+        assert!(uf.connected(0, 1));
+        assert!(uf.connected(2, 3));
+        assert!(!uf.connected(1, 3));
+        assert_eq!(2, uf.get(0).size());
+        assert_eq!(2, uf.get(3).size());
+        assert_eq!(4, uf.set_count());
+
+        let k6 = uf.insert(Default::default());
+        assert!(uf.union(k6, 0));
+        uf.rollback_to(c0);
+        assert_eq!(7, uf.set_count());
+        for key in 0..7 {
+            assert_eq!(key, uf.find(key));
+            assert_eq!(1, uf.get(key).size());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn rollback_past() {
+        let mut uf = crate::RollbackUf::<crate::UnionBySize>::new(2);
+        assert!(uf.union(0, 1));
+        let checkpoint = uf.snapshot();
+        assert!(uf.rollback());
+        uf.rollback_to(checkpoint);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rollback_past_and_redo() {
+        let mut uf = crate::RollbackUf::<crate::UnionBySize>::new(4);
+        assert!(uf.union(0, 1));
+        let checkpoint = uf.snapshot();
+        assert!(uf.rollback());
+        assert!(uf.union(2, 3));
+        uf.rollback_to(checkpoint);
+    }
+}
+mod persistent {
+    use crate::UnionFind;
+
+    #[test]
+    fn versions() {
+        let base = crate::PersistentUf::<crate::UnionBySize>::new(2000);
+        assert_eq!(2000, base.size());
+        assert_eq!(2000, base.set_count());
+
+        let mut versions = vec![(base, crate::QuickUnionUf::<crate::UnionBySize>::new(2000))];
+        for (i, (a, b)) in super::edges(2000, 600).into_iter().enumerate() {
+            let (uf, quick) = &versions[i / 3];
+            let uf = uf.union(a, b);
+            let mut quick = quick.clone();
+            let _ = quick.union(a, b);
+            versions.push((uf, quick));
+        }
+
+        for (uf, quick) in &mut versions {
+            assert_eq!(quick.set_count(), uf.set_count());
+            for key in (0..2000).step_by(7) {
+                assert_eq!(quick.get(key).size(), uf.get(key).size());
+                assert!(uf.connected(key, quick.find(key)));
+            }
+        }
+    }
+
+    #[test]
+    fn insert() {
+        let base = crate::PersistentUf::<crate::UnionBySize>::new(40);
+        let (uf, k40) = base.insert(Default::default());
+        assert_eq!(40, k40);
+        assert_eq!(40, base.size());
+        assert_eq!(41, uf.size());
+
+        let uf = uf.union(k40, 3);
+        assert!(uf.connected(3, 40));
+        assert_eq!(2, uf.get(40).size());
+        assert_eq!(40, uf.set_count());
+        assert_eq!(40, base.set_count());
+    }
+
+    #[test]
+    fn threads() {
+        let base = crate::PersistentUf::<crate::UnionBySize>::new(10).union(0, 1);
+        let versions = std::thread::scope(|s| {
+            let base = &base;
+            let handles = (2..10)
+                .map(|key| s.spawn(move || base.union(1, key)))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(2, base.get(0).size());
+        for (key, uf) in (2..10).zip(&versions) {
+            assert!(uf.connected(0, key));
+            assert_eq!(3, uf.get(key).size());
+        }
+    }
+}
+mod concurrent {
+    use crate::UnionFind;
+    use std::thread;
+
+    #[test]
+    fn union_find() {
+        const LEN: usize = 10_000;
+
+        let edges = super::edges(LEN, 8000);
+
+        let uf = crate::ConcurrentUf::new(LEN);
+        let merged = thread::scope(|s| {
+            let handles = edges
+                .chunks(1000)
+                .map(|edges| {
+                    let uf = &uf;
+                    s.spawn(move || edges.iter().filter(|&&(a, b)| uf.union(a, b)).count())
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .sum::<usize>()
+        });
+
+        let mut quick = crate::QuickUnionUf::<crate::UnionBySize>::new(LEN);
+        for &(a, b) in &edges {
+            let _ = quick.union(a, b);
+        }
+        assert_eq!(LEN - merged, quick.set_count());
+        for key in 0..LEN {
+            assert!(uf.connected(key, quick.find(key)));
+        }
+
+        let mut uf: crate::QuickFindUf<crate::UnionBySize> = uf.into_union_find();
+        assert_eq!(quick.set_count(), uf.set_count());
+        for key in 0..LEN {
+            assert_eq!(quick.get(key).size(), uf.get(key).size());
+        }
+    }
+}
+mod policy {
+    use crate::UnionFind;
+
+    #[derive(Clone, Debug)]
+    struct MinMax(usize, usize);
+
+    impl crate::Merge for MinMax {
+        fn merge(left: MinMax, right: MinMax) -> MinMax {
+            MinMax(left.0.min(right.0), left.1.max(right.1))
+        }
+    }
+
+    #[test]
+    fn merge() {
+        type Value = (crate::UnionByRank, MinMax);
+
+        let mut uf = (0..300)
+            .map(|i| (Default::default(), MinMax(i, i)))
+            .collect::<crate::QuickFindUf<Value>>();
+        let mut quick = crate::QuickUnionUf::<crate::UnionBySize>::new(300);
+        for (a, b) in super::edges(300, 200) {
+            assert_eq!(quick.union(a, b), uf.union(a, b));
+        }
+        for key in 0..300 {
+            let members = uf.members(key).collect::<Vec<_>>();
+            let &(_, MinMax(min, max)) = uf.get(key);
+            assert_eq!(Some(&min), members.iter().min());
+            assert_eq!(Some(&max), members.iter().max());
+            assert_eq!(quick.get(key).size(), members.len());
+        }
+    }
+
+    #[test]
+    fn detach() {
+        type Value = (crate::UnionBySize, crate::Sum<u32>);
+
+        let value = |n| (crate::UnionBySize::default(), crate::Sum(n));
+        let mut quick = (1..=4).map(value).collect::<crate::QuickUnionUf<Value>>();
+        let mut find = (1..=4).map(value).collect::<crate::QuickFindUf<Value>>();
+        for i in 0..3 {
+            assert!(quick.union(i, i + 1));
+            assert!(find.union(i, i + 1));
+        }
+
+        // The value of the element is removed from the value of its set.
+        quick.isolate(1, value(2));
+        find.isolate(1, value(2));
+        for (size, total) in [quick.get(0), find.get(0)] {
+            assert_eq!(3, size.size());
+            assert_eq!(crate::Sum(8), *total);
+        }
+        for (size, total) in [quick.get(1), find.get(1)] {
+            assert_eq!(1, size.size());
+            assert_eq!(crate::Sum(2), *total);
+        }
+    }
+
+    #[test]
+    fn split() {
+        type Value = (crate::UnionByIndex, MinMax);
+
+        let value = |i| (crate::UnionByIndex::new(i), MinMax(i, i));
+        let mut uf = (0..300).map(value).collect::<crate::QuickFindUf<Value>>();
+        for (a, b) in super::edges(300, 400) {
+            let _ = uf.union(a, b);
+        }
+        let key = (0..300).max_by_key(|&k| uf.members(k).count()).unwrap();
+        let (r0, r1) = uf.split_by(key, |k| k % 2 == 0, value).unwrap();
+        for root in [r0, r1] {
+            let members = uf.members(root).collect::<Vec<_>>();
+            let &(index, MinMax(min, max)) = uf.get(root);
+            assert_eq!(Some(&root), members.iter().min());
+            assert_eq!(root, index.index());
+            assert_eq!(Some(&min), members.iter().min());
+            assert_eq!(Some(&max), members.iter().max());
+        }
+    }
+
+    #[test]
+    fn by_index() {
+        let mut uf = (0..300)
+            .map(crate::UnionByIndex::new)
+            .collect::<crate::QuickUnionUf<crate::UnionByIndex>>();
+        for (a, b) in super::edges(300, 200) {
+            let _ = uf.union(a, b);
+        }
+        for (root, value, members) in uf.sets() {
+            assert_eq!(Some(&root), members.iter().min());
+            assert_eq!(root, value.index());
+        }
+    }
+
+    #[test]
+    fn by_random() {
+        let values = (0..300).map(crate::UnionByRandom::new).collect::<Vec<_>>();
+        assert_eq!(
+            crate::UnionByRandom::new(7).priority(),
+            values[7].priority()
+        );
+        let mut uf = values
+            .iter()
+            .copied()
+            .collect::<crate::QuickUnionUf<crate::UnionByRandom>>();
+        let mut quick = crate::QuickUnionUf::<crate::UnionBySize>::new(300);
+        for (a, b) in super::edges(300, 200) {
+            assert_eq!(quick.union(a, b), uf.union(a, b));
+        }
+        for (root, value, members) in uf.sets() {
+            assert_eq!(values[root].priority(), value.priority());
+            for key in members {
+                assert!(values[key].priority() <= value.priority());
+                assert!(quick.connected(key, root));
+            }
+        }
+    }
+}
+mod weighted {
+    use crate::UnionFind;
+
+    #[test]
+    fn union_with() {
+        const LEN: usize = 2000;
+
+        let potential = (0..LEN as i64).map(|i| i * 7919 % 1009).collect::<Vec<_>>();
+        let mut uf = crate::WeightedUf::<i64>::new(LEN);
+        let mut quick = crate::QuickUnionUf::<crate::UnionBySize>::new(LEN);
+        for (a, b) in super::edges(LEN, 1500) {
+            let merged = quick.union(a, b);
+            assert_eq!(Ok(merged), uf.union_with(a, b, potential[a] - potential[b]));
+        }
+        assert_eq!(quick.set_count(), uf.set_count());
+
+        for a in (0..LEN).step_by(13) {
+            for b in (0..LEN).step_by(17) {
+                let expected = quick.connected(a, b).then(|| potential[a] - potential[b]);
+                assert_eq!(expected, uf.diff(a, b));
+            }
+        }
+
+        let a = 5;
+        let b = (0..LEN).find(|&b| b != a && uf.connected(a, b)).unwrap();
+        let actual = potential[a] - potential[b];
+        assert_eq!(
+            Err(crate::Conflict {
+                expected: actual + 1,
+                actual,
+            }),
+            uf.union_with(a, b, actual + 1)
+        );
+        assert_eq!(Some(actual), uf.diff(a, b));
+    }
+
+    #[test]
+    fn bipartite() {
+        // A grid graph is bipartite, and the side of a cell is the parity of
+        // the sum of its coordinates.
+        const W: usize = 30;
+        let mut uf = crate::BipartiteUf::new(W * W);
+        for y in 0..W {
+            for x in 0..W {
+                if x + 1 < W {
+                    assert!(uf.add_edge(y * W + x, y * W + x + 1).is_ok());
+                }
+                if y + 1 < W {
+                    assert!(uf.add_edge(y * W + x, (y + 1) * W + x).is_ok());
+                }
+            }
+        }
+        assert_eq!(1, uf.set_count());
+        assert_eq!(Some(true), uf.same_side(0, W + 1));
+        assert_eq!(Some(false), uf.same_side(0, W * W - 2));
+
+        // A diagonal edge closes an odd cycle.
+        let err = uf.add_edge(0, W + 1).unwrap_err();
+        assert_eq!(crate::Parity::Even, err.actual);
+        assert_eq!(Some(true), uf.same_side(0, W + 1));
+
+        let key = uf.insert();
+        assert_eq!(None, uf.same_side(0, key));
+        assert_eq!(Ok(true), uf.add_edge(W + 1, key));
+        assert_eq!(Ok(false), uf.add_edge(0, key));
+    }
+}
+#[cfg(feature = "serde")]
+mod serialize {
+    use crate::UnionFind;
+
+    type QuickUnionUf = crate::QuickUnionUf<crate::UnionBySize>;
+    type QuickFindUf = crate::QuickFindUf<crate::UnionBySize>;
+
+    #[test]
+    fn quick_union() {
+        super::serde_round_trip::<QuickUnionUf>();
+
+        let mut uf = QuickUnionUf::new(3);
+        let _ = uf.union(0, 1);
+        uf.isolate(0, Default::default());
+        let mut loaded: QuickUnionUf =
+            serde_json::from_str(&serde_json::to_string(&uf).unwrap()).unwrap();
+        assert_eq!(uf.sets().count(), loaded.sets().count());
+        assert_eq!(3, loaded.size());
+        assert!(!loaded.connected(0, 1));
+    }
+
+    #[test]
+    fn quick_union_invalid() {
+        let invalid = [
+            r#"{"link_parent":[0,0],"payload":[{"size":2}]}"#,
+            r#"{"link_parent":[0,5],"payload":[{"size":2},null]}"#,
+            r#"{"link_parent":[1,2,1],"payload":[null,null,null]}"#,
+            r#"{"link_parent":[0,1],"payload":[{"size":1},null]}"#,
+            r#"{"link_parent":[0,0],"payload":[{"size":1},{"size":1}]}"#,
+        ];
+        for json in invalid {
+            assert!(
+                serde_json::from_str::<QuickUnionUf>(json).is_err(),
+                "{json}"
+            );
+        }
+    }
+
+    #[test]
+    fn quick_find() {
+        super::serde_round_trip::<QuickFindUf>();
+
+        let mut uf = QuickFindUf::new(4);
+        let _ = uf.union(3, 1);
+        let _ = uf.union(3, 2);
+        let loaded: QuickFindUf =
+            serde_json::from_str(&serde_json::to_string(&uf).unwrap()).unwrap();
+        assert_eq!(vec![3, 1, 2], loaded.members(1).collect::<Vec<_>>());
+        assert_eq!(vec![0], loaded.members(0).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn quick_find_invalid() {
+        let invalid = [
+            r#"{"link_root":[0,0],"payload":[{"size":2}]}"#,
+            r#"{"link_root":[0,5],"payload":[{"size":2},null]}"#,
+            r#"{"link_root":[1,2,2],"payload":[null,null,{"size":3}]}"#,
+            r#"{"link_root":[0,1],"payload":[{"size":1},null]}"#,
+            r#"{"link_root":[0,0],"payload":[{"size":1},{"size":1}]}"#,
+        ];
+        for json in invalid {
+            assert!(serde_json::from_str::<QuickFindUf>(json).is_err(), "{json}");
+        }
+    }
+}
+mod binary {
+    use crate::UnionFind;
+    use std::io;
+
+    type QuickUnionUf = crate::QuickUnionUf<crate::UnionBySize>;
+    type QuickUnionView<'a> = crate::QuickUnionView<'a, crate::UnionBySize>;
+
+    fn snapshot() -> (QuickUnionUf, Vec<u8>) {
+        let mut uf = QuickUnionUf::new(500);
+        for (a, b) in super::edges(500, 300) {
+            let _ = uf.union(a, b);
+        }
+        let mut bytes = vec![];
+        uf.write_to(&mut bytes).unwrap();
+        (uf, bytes)
+    }
+
+    #[test]
+    fn round_trip() {
+        let (mut uf, bytes) = snapshot();
+        assert_eq!(40 + 500 * 8 + 500 * 8, bytes.len());
+
+        let mut loaded = QuickUnionUf::read_from(&bytes[..]).unwrap();
+        let view = QuickUnionView::from_bytes(&bytes).unwrap();
+        assert_eq!(uf.set_count(), loaded.set_count());
+        assert_eq!(uf.set_count(), view.set_count());
+        assert_eq!(500, view.size());
+        for key in 0..500 {
+            assert_eq!(uf.find(key), loaded.find(key));
+            assert_eq!(uf.find(key), view.find(key));
+            assert_eq!(uf.get(key).size(), view.get(key).size());
+        }
+
+        let _ = uf.union(7, 8);
+        uf.isolate(7, Default::default());
+        let mut bytes = vec![];
+        uf.write_to(&mut bytes).unwrap();
+        let loaded = QuickUnionUf::read_from(&bytes[..]).unwrap();
+        let view = QuickUnionView::from_bytes(&bytes).unwrap();
+        assert_eq!(uf.sets().count(), loaded.sets().count());
+        assert_eq!(500, view.size());
+        assert!(!loaded.connected_immutable(7, 8));
+        assert_eq!(1, view.get(7).size());
+    }
+
+    #[test]
+    fn invalid() {
+        let (_, bytes) = snapshot();
+        let parent_at = |key: usize| 40 + key * 8;
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        let mut out_of_range = bytes.clone();
+        out_of_range[parent_at(3)..parent_at(4)].copy_from_slice(&500u64.to_le_bytes());
+        let mut cycle = bytes.clone();
+        cycle[parent_at(0)..parent_at(1)].copy_from_slice(&1u64.to_le_bytes());
+        cycle[parent_at(1)..parent_at(2)].copy_from_slice(&0u64.to_le_bytes());
+        let mut set_count = bytes.clone();
+        set_count[24] ^= 1;
+
+        for bytes in [
+            &bad_magic[..],
+            &out_of_range,
+            &cycle,
+            &set_count,
+            &bytes[..bytes.len() - 1],
+        ] {
+            let err = QuickUnionView::from_bytes(bytes).unwrap_err();
+            assert_eq!(io::ErrorKind::InvalidData, err.kind());
+            assert!(QuickUnionUf::read_from(bytes).is_err());
+        }
+
+        // The size of the values differs.
+        assert!(crate::QuickUnionView::<crate::UnionByRank>::from_bytes(&bytes).is_err());
+        assert!(crate::QuickUnionUf::<crate::UnionByRank>::read_from(&bytes[..]).is_err());
+    }
+}
+mod keyed {
+    type KeyedUf<K> =
+        crate::KeyedUf<K, crate::UnionBySize, crate::QuickUnionUf<crate::UnionBySize>>;
+
+    #[test]
+    fn union_find() {
+        let mut uf = KeyedUf::<(i32, i32)>::new();
+        assert_eq!(0, uf.size());
+        assert!(uf.insert((0, 0), Default::default()));
+        assert!(!uf.insert((0, 0), Default::default()));
+        assert!(uf.union(&(0, 0), &(0, 1)));
+        assert!(uf.union(&(1, 1), &(0, 1)));
+        assert!(uf.union(&(5, 5), &(5, 6)));
+        assert!(!uf.union(&(1, 1), &(0, 0)));
+        assert_eq!(5, uf.size());
+        assert_eq!(2, uf.set_count());
+
+        assert_eq!(uf.find(&(0, 0)).clone(), uf.find(&(1, 1)).clone());
+        assert_ne!(uf.find(&(0, 0)).clone(), uf.find(&(5, 6)).clone());
+        assert!(uf.connected(&(0, 1), &(1, 1)));
+        assert!(!uf.connected(&(0, 1), &(5, 5)));
+        assert!(!uf.connected(&(0, 1), &(9, 9)));
+        assert!(!uf.contains(&(9, 9)));
+        assert_eq!(3, uf.get(&(1, 1)).size());
+        assert_eq!(2, uf.get(&(5, 5)).size());
+
+        assert_eq!(1, uf.get(&(9, 9)).size());
+        assert!(uf.contains(&(9, 9)));
+        assert_eq!(6, uf.size());
+        assert_eq!(3, uf.set_count());
+
+        let index = uf.index_of(&(5, 6)).unwrap();
+        assert_eq!(&(5, 6), uf.elem_of(index));
+    }
+}
+#[cfg(feature = "derive")]
+mod derive {
+    use crate::UnionFind;
+    use std::collections::BTreeSet;
+
+    fn concat(mut left: Vec<u32>, right: Vec<u32>) -> Vec<u32> {
+        left.extend(right);
+        left
+    }
+
+    #[derive(Clone, Debug, crate::Union, crate::Split)]
+    struct Stats {
+        #[union(link)]
+        size: crate::UnionBySize,
+        total: crate::Sum<u32>,
+        low: crate::Min<u32>,
+        high: crate::Max<u32>,
+        flagged: crate::Or,
+        #[union(with = concat)]
+        items: Vec<u32>,
+    }
+
+    fn join(mut left: BTreeSet<u32>, right: BTreeSet<u32>) -> BTreeSet<u32> {
+        left.extend(right);
+        left
+    }
+
+    fn remove(set: BTreeSet<u32>, removed: &BTreeSet<u32>) -> BTreeSet<u32> {
+        &set - removed
+    }
+
+    #[derive(Clone, Debug, crate::Union, crate::Detach)]
+    struct Totals {
+        #[union(link)]
+        size: crate::UnionBySize,
+        total: crate::Sum<u32>,
+        #[union(with = join, detach = remove)]
+        items: BTreeSet<u32>,
+    }
+
+    #[derive(Clone, Debug, crate::Union)]
+    struct Pair(crate::Sum<u32>, #[union(link)] crate::UnionByRank);
+
+    fn stats(n: u32) -> Stats {
+        Stats {
+            size: Default::default(),
+            total: crate::Sum(n),
+            low: crate::Min(n),
+            high: crate::Max(n),
+            flagged: crate::Or(n == 7),
+            items: vec![n],
+        }
+    }
+
+    #[test]
+    fn named_fields() {
+        let mut uf = [5, 7, 2, 9]
+            .into_iter()
+            .map(stats)
+            .collect::<crate::QuickUnionUf<Stats>>();
+        assert!(uf.union(0, 1));
+        assert!(uf.union(2, 0));
+
+        let s = uf.get(1);
+        assert_eq!(3, s.size.size());
+        assert_eq!(crate::Sum(14), s.total);
+        assert_eq!(crate::Min(2), s.low);
+        assert_eq!(crate::Max(7), s.high);
+        assert_eq!(crate::Or(true), s.flagged);
+        let mut items = s.items.clone();
+        items.sort_unstable();
+        assert_eq!(vec![2, 5, 7], items);
+
+        let s = uf.get(3);
+        assert_eq!(1, s.size.size());
+        assert_eq!(crate::Or(false), s.flagged);
+    }
+
+    #[test]
+    fn detach() {
+        let totals = |n| Totals {
+            size: Default::default(),
+            total: crate::Sum(n),
+            items: BTreeSet::from([n]),
+        };
+        let mut quick = [5, 7, 2]
+            .into_iter()
+            .map(totals)
+            .collect::<crate::QuickUnionUf<Totals>>();
+        let mut find = [5, 7, 2]
+            .into_iter()
+            .map(totals)
+            .collect::<crate::QuickFindUf<Totals>>();
+        for i in 0..2 {
+            assert!(quick.union(i, i + 1));
+            assert!(find.union(i, i + 1));
+        }
+
+        quick.isolate(1, totals(7));
+        find.isolate(1, totals(7));
+        for t in [quick.get(0), find.get(0)] {
+            assert_eq!(2, t.size.size());
+            assert_eq!(crate::Sum(7), t.total);
+            assert_eq!(BTreeSet::from([2, 5]), t.items);
+        }
+        for t in [quick.get(1), find.get(1)] {
+            assert_eq!(1, t.size.size());
+            assert_eq!(crate::Sum(7), t.total);
+        }
+    }
+
+    #[test]
+    fn split() {
+        let mut uf = (0..10).map(stats).collect::<crate::QuickFindUf<Stats>>();
+        for i in 0..9 {
+            assert!(uf.union(i, i + 1));
+        }
+
+        let (r0, r1) = uf.split_by(4, |k| k % 5 == 2, |k| stats(k as u32)).unwrap();
+        let s = uf.get(r0);
+        assert_eq!(2, s.size.size());
+        assert_eq!(crate::Sum(9), s.total);
+        assert_eq!(crate::Min(2), s.low);
+        assert_eq!(crate::Max(7), s.high);
+        assert_eq!(crate::Or(true), s.flagged);
+        assert_eq!(vec![2, 7], s.items);
+        let s = uf.get(r1);
+        assert_eq!(8, s.size.size());
+        assert_eq!(crate::Sum(36), s.total);
+        assert_eq!(crate::Min(0), s.low);
+        assert_eq!(crate::Max(9), s.high);
+        assert_eq!(crate::Or(false), s.flagged);
+    }
+
+    #[test]
+    fn tuple_fields() {
+        let mut uf = (1..=4)
+            .map(|n| Pair(crate::Sum(n), Default::default()))
+            .collect::<crate::QuickFindUf<Pair>>();
+        assert!(uf.union(0, 1));
+        assert!(uf.union(2, 3));
+        assert!(uf.union(0, 3));
+        assert_eq!(crate::Sum(10), uf.get(2).0);
+        assert_eq!(2, uf.get(2).1.rank());
+    }
 }
@@ -5,6 +5,9 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use crate::{Idx, UfError};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use std::iter::FromIterator;
 
 /// The value that can be contained with `Union`.
@@ -15,6 +18,89 @@ pub trait Union: Sized {
     fn union(lval: Self, rval: Self) -> UnionResult<Self>;
 }
 
+/// The value that a single element can be removed from along with its set.
+///
+/// The values that cannot be recomputed without the element, such as the
+/// smallest key of `UnionByIndex`, do not implement `Detach`.
+pub trait Detach: Union {
+    /// Removes a single element from a set that has other elements.
+    ///
+    /// `set` is the value of the set that contained the element, and
+    /// `removed` is the value of the element itself. Returns the value of the
+    /// set without the element.
+    ///
+    /// This is used by the `isolate` operations.
+    fn detach(set: Self, removed: &Self) -> Self;
+}
+
+/// The data of a set that is merged when two sets are joined.
+///
+/// Unlike `Union`, `Merge` does not decide how the sets are linked. A pair of
+/// a linking policy and the data, `(P, D)`, implements `Union`, where the
+/// policy `P` decides the linking and the data `D` is merged. The pair is
+/// the value type of the structures by design: the second type parameter of
+/// the structures is the key type, so the policy is not a parameter of its
+/// own. The pair implements `Detach` if the data implements `Unmerge`.
+///
+/// ```
+/// use union_find::{Merge, QuickUnionUf, UnionBySize, UnionFind};
+///
+/// #[derive(Clone, Debug, Default)]
+/// struct Total(u64);
+///
+/// impl Merge for Total {
+///     fn merge(left: Total, right: Total) -> Total {
+///         Total(left.0 + right.0)
+///     }
+/// }
+///
+/// let mut uf: QuickUnionUf<(UnionBySize, Total)> = (1..=4)
+///     .map(|n| (UnionBySize::default(), Total(n)))
+///     .collect();
+/// uf.union(0, 1);
+/// uf.union(1, 2);
+/// let (policy, total) = uf.get(2);
+/// assert_eq!(policy.size(), 3);
+/// assert_eq!(total.0, 6);
+/// ```
+pub trait Merge {
+    /// Merges the data of two sets into one.
+    fn merge(left: Self, right: Self) -> Self;
+}
+
+impl Merge for () {
+    #[inline]
+    fn merge(_left: (), _right: ()) {}
+}
+
+/// The data of a set that the data of a single element can be removed from.
+///
+/// This is the inverse of `Merge`, so it is not implemented by the data that
+/// loses information when merged, such as `Min`, `Max` and `Or`.
+pub trait Unmerge: Merge {
+    /// Removes the data of a single element, `removed`, from the data of a
+    /// set that contains it.
+    fn unmerge(set: Self, removed: &Self) -> Self;
+}
+
+impl Unmerge for () {
+    #[inline]
+    fn unmerge(_set: (), _removed: &()) {}
+}
+
+/// The value that can be split along with its set.
+pub trait Split: Union {
+    /// Splits the value of a set into the values of two parts of the set.
+    ///
+    /// `part0` and `part1` are the values of the elements of each part, and
+    /// neither of them is empty. The data merged by `Merge` is recomputed
+    /// from them. The rank policies keep the rank of the original set in
+    /// both parts, as it is still an upper bound of their heights.
+    ///
+    /// This is used by `QuickFindUf::split_by` operation.
+    fn split(set: Self, part0: Vec<Self>, part1: Vec<Self>) -> (Self, Self);
+}
+
 /// Return value of the [`Union::union`].
 #[allow(missing_docs)]
 #[derive(Copy, Clone, Debug)]
@@ -23,8 +109,45 @@ pub enum UnionResult<T> {
     Right(T),
 }
 
+/// Return value of the [`UnionFind::union_detailed`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UnionOutcome<K> {
+    /// The two sets have been joined into one.
+    Merged {
+        /// The identifier of the joined set.
+        root: K,
+        /// The identifier of the other set, which is no longer a root.
+        absorbed: K,
+    },
+    /// The keys already belonged to the same set.
+    AlreadyJoined {
+        /// The identifier of the set.
+        root: K,
+    },
+}
+
+impl<K: Copy> UnionOutcome<K> {
+    /// Returns the identifier of the set that contains both keys.
+    #[inline]
+    pub fn root(&self) -> K {
+        match *self {
+            UnionOutcome::Merged { root, .. } | UnionOutcome::AlreadyJoined { root } => root,
+        }
+    }
+
+    /// Returns `true` if two sets have been joined.
+    #[inline]
+    pub fn is_merged(&self) -> bool {
+        matches!(self, UnionOutcome::Merged { .. })
+    }
+}
+
 /// APIs for Union-Find operation.
-pub trait UnionFind<V: Union>: FromIterator<V> + Extend<V> + Sized {
+///
+/// The keys of the sets are of type `K`, which is `usize` by default.
+/// The operations that join sets require the values `V` to implement
+/// `Union`, while the others work with any values.
+pub trait UnionFind<V, K: Idx = usize>: FromIterator<V> + Extend<V> + Sized {
     /// Creates empty `UnionFind` struct.
     #[inline]
     fn new(len: usize) -> Self
@@ -37,22 +160,160 @@ pub trait UnionFind<V: Union>: FromIterator<V> + Extend<V> + Sized {
     /// Returns the size of `self`.
     fn size(&self) -> usize;
 
+    /// Returns the number of disjoint sets in `self`.
+    ///
+    /// This is maintained by `insert`, `extend` and `union`, so it takes O(1)
+    /// time.
+    fn set_count(&self) -> usize;
+
     /// Inserts a new set into the union.
     ///
     /// Returns the key of the inserted set.
-    fn insert(&mut self, data: V) -> usize;
+    fn insert(&mut self, data: V) -> K;
 
     /// Join two sets that contains given keys (union operation).
     ///
     /// Returns `true` if these keys are belonged to different sets.
-    fn union(&mut self, key0: usize, key1: usize) -> bool;
+    fn union(&mut self, key0: K, key1: K) -> bool
+    where
+        V: Union;
+
+    /// Join two sets that contains given keys (union operation), and reports
+    /// which set has been absorbed into which.
+    ///
+    /// The default implementation is a fallback for implementations outside
+    /// this crate: it calls `find` on the keys, `union` on the roots and
+    /// `find` again to tell the new root, so it costs extra `find` calls.
+    /// Implementations should override it with their own linking step, and
+    /// implement `union` as `union_detailed(..).is_merged()`, as the
+    /// implementations in this crate do.
+    #[inline]
+    fn union_detailed(&mut self, key0: K, key1: K) -> UnionOutcome<K>
+    where
+        V: Union,
+    {
+        let k0 = self.find(key0);
+        let k1 = self.find(key1);
+        if k0 == k1 {
+            return UnionOutcome::AlreadyJoined { root: k0 };
+        }
+        let _ = self.union(k0, k1);
+        let root = self.find(k0);
+        let absorbed = if root == k0 { k1 } else { k0 };
+        UnionOutcome::Merged { root, absorbed }
+    }
+
+    /// Join the sets of all pairs of keys.
+    ///
+    /// The pairs are processed in parallel by a `ConcurrentUf`, and then its
+    /// partition is applied to `self` with one `union` per key. The resulting
+    /// partition is the same as calling `union` for each pair sequentially.
+    #[cfg(feature = "rayon")]
+    fn union_all_par(&mut self, pairs: &[(K, K)])
+    where
+        V: Union,
+        K: Sync,
+    {
+        let uf = crate::ConcurrentUf::new(self.size());
+        pairs.par_iter().for_each(|&(key0, key1)| {
+            let _ = uf.union(key0.index(), key1.index());
+        });
+        uf.apply_to(self);
+    }
 
     /// Returns the identifier of the set that the key belongs to.
-    fn find(&mut self, key: usize) -> usize;
+    fn find(&mut self, key: K) -> K;
 
     /// Returns the reference to the value of the set that the key belongs to.
-    fn get(&mut self, key: usize) -> &V;
+    fn get(&mut self, key: K) -> &V;
 
     /// Returns the mutable reference to the value of the set that the key belongs to.
-    fn get_mut(&mut self, key: usize) -> &mut V;
+    fn get_mut(&mut self, key: K) -> &mut V;
+
+    /// Returns the identifier of the set that the key belongs to, or an error
+    /// if the key is out of range.
+    #[inline]
+    fn try_find(&mut self, key: K) -> Result<K, UfError> {
+        check_key(self.size(), key)?;
+        Ok(self.find(key))
+    }
+
+    /// Join two sets that contains given keys (union operation), or returns
+    /// an error if either key is out of range.
+    ///
+    /// `self` is not modified when an error is returned.
+    #[inline]
+    fn try_union(&mut self, key0: K, key1: K) -> Result<bool, UfError>
+    where
+        V: Union,
+    {
+        check_key(self.size(), key0)?;
+        check_key(self.size(), key1)?;
+        Ok(self.union(key0, key1))
+    }
+
+    /// Returns the reference to the value of the set that the key belongs
+    /// to, or an error if the key is out of range.
+    #[inline]
+    fn try_get(&mut self, key: K) -> Result<&V, UfError> {
+        check_key(self.size(), key)?;
+        Ok(self.get(key))
+    }
+
+    /// Returns the mutable reference to the value of the set that the key
+    /// belongs to, or an error if the key is out of range.
+    #[inline]
+    fn try_get_mut(&mut self, key: K) -> Result<&mut V, UfError> {
+        check_key(self.size(), key)?;
+        Ok(self.get_mut(key))
+    }
+
+    /// Returns `true` if given keys belong to the same set.
+    #[inline]
+    fn connected(&mut self, key0: K, key1: K) -> bool {
+        self.find(key0) == self.find(key1)
+    }
+
+    /// Returns whether each pair of keys belongs to the same set.
+    ///
+    /// The pairs are queried in order, so paths compressed by earlier queries
+    /// speed up later ones.
+    #[inline]
+    fn connected_many(&mut self, pairs: &[(K, K)]) -> Vec<bool> {
+        pairs
+            .iter()
+            .map(|&(key0, key1)| self.connected(key0, key1))
+            .collect()
+    }
+
+    /// Returns the identifier of the set that the key belongs to, without
+    /// modifying `self`.
+    ///
+    /// Unlike `find`, this does not compress paths, so it can be called through
+    /// a shared reference.
+    fn find_immutable(&self, key: K) -> K;
+
+    /// Returns the reference to the value of the set that the key belongs to,
+    /// without modifying `self`.
+    fn get_immutable(&self, key: K) -> &V;
+
+    /// Returns `true` if given keys belong to the same set, without modifying
+    /// `self`.
+    #[inline]
+    fn connected_immutable(&self, key0: K, key1: K) -> bool {
+        self.find_immutable(key0) == self.find_immutable(key1)
+    }
+}
+
+/// Returns an error if the key is out of range for `size`.
+#[inline]
+pub(crate) fn check_key<K: Idx>(size: usize, key: K) -> Result<(), UfError> {
+    if key.index() < size {
+        Ok(())
+    } else {
+        Err(UfError::KeyOutOfRange {
+            key: key.index(),
+            size,
+        })
+    }
 }
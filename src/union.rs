@@ -5,7 +5,8 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use crate::{Union, UnionResult};
+use crate::binary::{decode_size, encode_size};
+use crate::{util, Codec, Detach, Merge, Split, Union, UnionResult, Unmerge};
 use std::cmp::Ordering;
 
 const DEFAULT_RANK: u8 = 0;
@@ -15,6 +16,7 @@ const DEFAULT_SIZE: usize = 1;
 ///
 /// A smaller sized set will be the children of a larger sized set.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnionBySize {
     size: usize,
 }
@@ -35,6 +37,43 @@ impl Union for UnionBySize {
     }
 }
 
+impl Detach for UnionBySize {
+    #[inline]
+    fn detach(set: UnionBySize, _removed: &UnionBySize) -> UnionBySize {
+        UnionBySize {
+            size: set.size.saturating_sub(1),
+        }
+    }
+}
+
+impl Split for UnionBySize {
+    #[inline]
+    fn split(
+        _set: UnionBySize,
+        part0: Vec<UnionBySize>,
+        part1: Vec<UnionBySize>,
+    ) -> (UnionBySize, UnionBySize) {
+        (
+            UnionBySize { size: part0.len() },
+            UnionBySize { size: part1.len() },
+        )
+    }
+}
+
+impl Codec for UnionBySize {
+    const SIZE: usize = 8;
+
+    #[inline]
+    fn encode(&self, buf: &mut [u8]) {
+        encode_size(self.size(), buf);
+    }
+
+    #[inline]
+    fn decode(buf: &[u8]) -> Option<UnionBySize> {
+        decode_size(buf).map(|size| UnionBySize { size })
+    }
+}
+
 impl Default for UnionBySize {
     #[inline]
     fn default() -> UnionBySize {
@@ -55,6 +94,7 @@ impl UnionBySize {
 /// A smaller ranked set will be the children of a larger ranked set.
 /// If both sets have the same rank, the rank of the resulting set is incremented.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnionByRank {
     rank: u8,
 }
@@ -72,6 +112,40 @@ impl Union for UnionByRank {
     }
 }
 
+impl Detach for UnionByRank {
+    /// The rank of the set is kept, as it is still an upper bound of the
+    /// height.
+    #[inline]
+    fn detach(set: UnionByRank, _removed: &UnionByRank) -> UnionByRank {
+        set
+    }
+}
+
+impl Split for UnionByRank {
+    #[inline]
+    fn split(
+        set: UnionByRank,
+        _part0: Vec<UnionByRank>,
+        _part1: Vec<UnionByRank>,
+    ) -> (UnionByRank, UnionByRank) {
+        (set, set)
+    }
+}
+
+impl Codec for UnionByRank {
+    const SIZE: usize = 1;
+
+    #[inline]
+    fn encode(&self, buf: &mut [u8]) {
+        buf[0] = self.rank();
+    }
+
+    #[inline]
+    fn decode(buf: &[u8]) -> Option<UnionByRank> {
+        Some(UnionByRank { rank: buf[0] })
+    }
+}
+
 impl Default for UnionByRank {
     fn default() -> Self {
         Self { rank: DEFAULT_RANK }
@@ -91,6 +165,7 @@ impl UnionByRank {
 /// A smaller sized set will be the children of a larger sized set.
 /// If both sets have the same size, compared by the rank.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnionBySizeRank {
     size: usize,
     rank: u8,
@@ -127,6 +202,51 @@ impl Union for UnionBySizeRank {
     }
 }
 
+impl Detach for UnionBySizeRank {
+    #[inline]
+    fn detach(set: UnionBySizeRank, _removed: &UnionBySizeRank) -> UnionBySizeRank {
+        UnionBySizeRank {
+            size: set.size.saturating_sub(1),
+            ..set
+        }
+    }
+}
+
+impl Split for UnionBySizeRank {
+    #[inline]
+    fn split(
+        set: UnionBySizeRank,
+        part0: Vec<UnionBySizeRank>,
+        part1: Vec<UnionBySizeRank>,
+    ) -> (UnionBySizeRank, UnionBySizeRank) {
+        (
+            UnionBySizeRank {
+                size: part0.len(),
+                ..set
+            },
+            UnionBySizeRank {
+                size: part1.len(),
+                ..set
+            },
+        )
+    }
+}
+
+impl Codec for UnionBySizeRank {
+    const SIZE: usize = 9;
+
+    #[inline]
+    fn encode(&self, buf: &mut [u8]) {
+        encode_size(self.size(), &mut buf[..8]);
+        buf[8] = self.rank();
+    }
+
+    #[inline]
+    fn decode(buf: &[u8]) -> Option<UnionBySizeRank> {
+        decode_size(&buf[..8]).map(|size| UnionBySizeRank { size, rank: buf[8] })
+    }
+}
+
 impl Default for UnionBySizeRank {
     #[inline]
     fn default() -> UnionBySizeRank {
@@ -156,6 +276,7 @@ impl UnionBySizeRank {
 /// A smaller ranked set will be the children of a larger ranked set.
 /// If both sets have the same rank, compared by the size.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnionByRankSize {
     rank: u8,
     size: usize,
@@ -192,6 +313,51 @@ impl Union for UnionByRankSize {
     }
 }
 
+impl Detach for UnionByRankSize {
+    #[inline]
+    fn detach(set: UnionByRankSize, _removed: &UnionByRankSize) -> UnionByRankSize {
+        UnionByRankSize {
+            size: set.size.saturating_sub(1),
+            ..set
+        }
+    }
+}
+
+impl Split for UnionByRankSize {
+    #[inline]
+    fn split(
+        set: UnionByRankSize,
+        part0: Vec<UnionByRankSize>,
+        part1: Vec<UnionByRankSize>,
+    ) -> (UnionByRankSize, UnionByRankSize) {
+        (
+            UnionByRankSize {
+                size: part0.len(),
+                ..set
+            },
+            UnionByRankSize {
+                size: part1.len(),
+                ..set
+            },
+        )
+    }
+}
+
+impl Codec for UnionByRankSize {
+    const SIZE: usize = 9;
+
+    #[inline]
+    fn encode(&self, buf: &mut [u8]) {
+        buf[0] = self.rank();
+        encode_size(self.size(), &mut buf[1..]);
+    }
+
+    #[inline]
+    fn decode(buf: &[u8]) -> Option<UnionByRankSize> {
+        decode_size(&buf[1..]).map(|size| UnionByRankSize { rank: buf[0], size })
+    }
+}
+
 impl Default for UnionByRankSize {
     #[inline]
     fn default() -> UnionByRankSize {
@@ -215,3 +381,198 @@ impl UnionByRankSize {
         self.size
     }
 }
+
+/// Operates the `union` with using the keys of the sets as weight.
+///
+/// The set with the smaller key will be the parent, so the identifier of
+/// a set is always its smallest key. As the value must know its key, it is
+/// created by `UnionByIndex::new` rather than `Default`.
+///
+/// It does not implement `Detach`, because the value of a set does not know
+/// its next smallest key once the smallest one is removed, so the sets
+/// cannot be used with `isolate`. `Split` recomputes the value of each part
+/// from the keys of its members instead.
+///
+/// ```
+/// use union_find::{QuickUnionUf, UnionByIndex, UnionFind};
+///
+/// let mut uf: QuickUnionUf<UnionByIndex> = (0..4).map(UnionByIndex::new).collect();
+/// uf.union(3, 1);
+/// uf.union(2, 3);
+/// assert_eq!(uf.find(3), 1);
+/// ```
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnionByIndex {
+    index: usize,
+}
+
+impl Union for UnionByIndex {
+    #[inline]
+    fn union(left: UnionByIndex, right: UnionByIndex) -> UnionResult<UnionByIndex> {
+        if left.index <= right.index {
+            UnionResult::Left(left)
+        } else {
+            UnionResult::Right(right)
+        }
+    }
+}
+
+impl Split for UnionByIndex {
+    #[inline]
+    fn split(
+        _set: UnionByIndex,
+        part0: Vec<UnionByIndex>,
+        part1: Vec<UnionByIndex>,
+    ) -> (UnionByIndex, UnionByIndex) {
+        let min = |part: Vec<UnionByIndex>| part.into_iter().min_by_key(|v| v.index).unwrap();
+        (min(part0), min(part1))
+    }
+}
+
+impl Codec for UnionByIndex {
+    const SIZE: usize = 8;
+
+    #[inline]
+    fn encode(&self, buf: &mut [u8]) {
+        encode_size(self.index, buf);
+    }
+
+    #[inline]
+    fn decode(buf: &[u8]) -> Option<UnionByIndex> {
+        decode_size(buf).map(|index| UnionByIndex { index })
+    }
+}
+
+impl UnionByIndex {
+    /// Creates the value of the set whose key is `index`.
+    #[inline]
+    pub fn new(index: usize) -> UnionByIndex {
+        UnionByIndex { index }
+    }
+
+    /// Returns the smallest key of the set.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// Operates the `union` with using random priorities of the sets as weight.
+///
+/// Each value gets a pseudo-random priority, and the set with the higher
+/// priority will be the parent. This keeps the trees shallow in expectation
+/// without maintaining sizes or ranks. The priority is derived from the key,
+/// so the value is created by `UnionByRandom::new` rather than `Default`, and
+/// the same keys are always linked in the same way.
+///
+/// ```
+/// use union_find::{QuickUnionUf, UnionByRandom, UnionFind};
+///
+/// let mut uf: QuickUnionUf<UnionByRandom> = (0..4).map(UnionByRandom::new).collect();
+/// uf.union(3, 1);
+/// uf.union(2, 3);
+/// assert!(uf.connected(1, 2));
+/// ```
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnionByRandom {
+    priority: u64,
+}
+
+impl Union for UnionByRandom {
+    #[inline]
+    fn union(left: UnionByRandom, right: UnionByRandom) -> UnionResult<UnionByRandom> {
+        if left.priority >= right.priority {
+            UnionResult::Left(left)
+        } else {
+            UnionResult::Right(right)
+        }
+    }
+}
+
+impl Detach for UnionByRandom {
+    /// The priority of the set is kept, as any priority of the set decides
+    /// the linking as well.
+    #[inline]
+    fn detach(set: UnionByRandom, _removed: &UnionByRandom) -> UnionByRandom {
+        set
+    }
+}
+
+impl Split for UnionByRandom {
+    #[inline]
+    fn split(
+        _set: UnionByRandom,
+        part0: Vec<UnionByRandom>,
+        part1: Vec<UnionByRandom>,
+    ) -> (UnionByRandom, UnionByRandom) {
+        let max = |part: Vec<UnionByRandom>| part.into_iter().max_by_key(|v| v.priority).unwrap();
+        (max(part0), max(part1))
+    }
+}
+
+impl Codec for UnionByRandom {
+    const SIZE: usize = 8;
+
+    #[inline]
+    fn encode(&self, buf: &mut [u8]) {
+        buf.copy_from_slice(&self.priority.to_le_bytes());
+    }
+
+    #[inline]
+    fn decode(buf: &[u8]) -> Option<UnionByRandom> {
+        Some(UnionByRandom {
+            priority: u64::from_le_bytes(buf.try_into().ok()?),
+        })
+    }
+}
+
+impl UnionByRandom {
+    /// Creates the value of the set whose key is `index`, with a priority
+    /// derived from the key.
+    #[inline]
+    pub fn new(index: usize) -> UnionByRandom {
+        UnionByRandom {
+            priority: util::priority(index),
+        }
+    }
+
+    /// Returns the priority of the set.
+    #[inline]
+    pub fn priority(&self) -> u64 {
+        self.priority
+    }
+}
+
+impl<P: Union, D: Merge> Union for (P, D) {
+    #[inline]
+    fn union(left: (P, D), right: (P, D)) -> UnionResult<(P, D)> {
+        let data = Merge::merge(left.1, right.1);
+        match Union::union(left.0, right.0) {
+            UnionResult::Left(policy) => UnionResult::Left((policy, data)),
+            UnionResult::Right(policy) => UnionResult::Right((policy, data)),
+        }
+    }
+}
+
+impl<P: Detach, D: Unmerge> Detach for (P, D) {
+    #[inline]
+    fn detach(set: (P, D), removed: &(P, D)) -> (P, D) {
+        (
+            Detach::detach(set.0, &removed.0),
+            Unmerge::unmerge(set.1, &removed.1),
+        )
+    }
+}
+
+impl<P: Split, D: Merge> Split for (P, D) {
+    #[inline]
+    fn split(set: (P, D), part0: Vec<(P, D)>, part1: Vec<(P, D)>) -> ((P, D), (P, D)) {
+        let (policy0, data0): (Vec<P>, Vec<D>) = part0.into_iter().unzip();
+        let (policy1, data1): (Vec<P>, Vec<D>) = part1.into_iter().unzip();
+        let (policy0, policy1) = Split::split(set.0, policy0, policy1);
+        let merge_all = |data: Vec<D>| data.into_iter().reduce(Merge::merge).unwrap();
+        ((policy0, merge_all(data0)), (policy1, merge_all(data1)))
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2016 union-find-rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::Idx;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
+
+/// Maps the root of each key to a dense index in first-seen order.
+pub(crate) fn dense_labels<K: Idx>(roots: &[K]) -> Vec<usize> {
+    const UNLABELED: usize = usize::MAX;
+
+    let mut root_labels = vec![UNLABELED; roots.len()];
+    let mut count = 0;
+    roots
+        .iter()
+        .map(|&root| {
+            let root = root.index();
+            if root_labels[root] == UNLABELED {
+                root_labels[root] = count;
+                count += 1;
+            }
+            root_labels[root]
+        })
+        .collect()
+}
+
+/// Returns a pseudo-random priority of the index.
+///
+/// This is a bijective mix of the bits of the index, so distinct indices have
+/// distinct priorities.
+#[inline]
+pub(crate) fn priority(index: usize) -> u64 {
+    let mut x = (index as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Checks that each of the `len` keys leads to a root that links to itself,
+/// where `parent` returns the parent link of a key.
+///
+/// Returns an error message if a link is out of range or forms a cycle.
+pub(crate) fn check_forest<F>(len: usize, parent: F) -> Result<(), &'static str>
+where
+    F: Fn(usize) -> usize,
+{
+    const UNVISITED: u8 = 0;
+    const VISITING: u8 = 1;
+    const VISITED: u8 = 2;
+
+    let mut state = vec![UNVISITED; len];
+    let mut path = vec![];
+    for key in 0..len {
+        let mut k = key;
+        while state[k] == UNVISITED {
+            state[k] = VISITING;
+            path.push(k);
+            let p = parent(k);
+            if p >= len {
+                return Err("parent link out of range");
+            }
+            if p == k {
+                break;
+            }
+            k = p;
+        }
+        if state[k] == VISITING && parent(k) != k {
+            return Err("cycle in parent links");
+        }
+        for k in path.drain(..) {
+            state[k] = VISITED;
+        }
+    }
+    Ok(())
+}
+
+/// Serializes keys as a sequence of their indices.
+#[cfg(feature = "serde")]
+pub(crate) struct Indices<'a, K>(pub(crate) &'a [K]);
+
+#[cfg(feature = "serde")]
+impl<K: Idx> Serialize for Indices<'_, K> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(|k| k.index()))
+    }
+}
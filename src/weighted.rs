@@ -0,0 +1,313 @@
+// Copyright 2016 union-find-rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::error::Error;
+use std::fmt;
+
+/// A commutative group of potentials used by `WeightedUf`.
+///
+/// The integer types form a group under wrapping addition.
+pub trait Group: Clone + PartialEq {
+    /// Returns the identity element.
+    fn identity() -> Self;
+    /// Returns the result of combining `self` with `other`.
+    fn combine(self, other: Self) -> Self;
+    /// Returns the inverse element of `self`.
+    fn inverse(self) -> Self;
+}
+
+macro_rules! impl_group_for_int {
+    ($($t:ty),*) => {$(
+        impl Group for $t {
+            #[inline]
+            fn identity() -> $t {
+                0
+            }
+
+            #[inline]
+            fn combine(self, other: $t) -> $t {
+                self.wrapping_add(other)
+            }
+
+            #[inline]
+            fn inverse(self) -> $t {
+                self.wrapping_neg()
+            }
+        }
+    )*};
+}
+
+impl_group_for_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// The parity of the number of edges on a path, which forms a group under
+/// XOR.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Parity {
+    /// Even number of edges.
+    Even,
+    /// Odd number of edges.
+    Odd,
+}
+
+impl Group for Parity {
+    #[inline]
+    fn identity() -> Parity {
+        Parity::Even
+    }
+
+    #[inline]
+    fn combine(self, other: Parity) -> Parity {
+        if self == other {
+            Parity::Even
+        } else {
+            Parity::Odd
+        }
+    }
+
+    #[inline]
+    fn inverse(self) -> Parity {
+        self
+    }
+}
+
+/// The error returned when a constraint contradicts the existing ones.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Conflict<G> {
+    /// The difference requested by the rejected constraint.
+    pub expected: G,
+    /// The difference implied by the existing constraints.
+    pub actual: G,
+}
+
+impl<G: fmt::Debug> fmt::Display for Conflict<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "inconsistent constraint: expected difference {:?}, but found {:?}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl<G: fmt::Debug> Error for Conflict<G> {}
+
+/// Union-Find implementation that tracks the differences between the
+/// potentials of the keys in each set.
+///
+/// Each key has a potential, which is an element of the group `G`, and each
+/// link stores the difference between the potential of the key and its
+/// parent. `union_with` adds a constraint `potential(key0) - potential(key1)
+/// = diff`, and `diff` returns the difference implied by the constraints
+/// added so far.
+///
+/// The sets are joined by size, and `find` halves paths in the same way as
+/// `QuickUnionUf`, composing the differences of the skipped links.
+///
+/// ```
+/// use union_find::WeightedUf;
+///
+/// let mut uf = WeightedUf::<i64>::new(4);
+/// assert_eq!(uf.union_with(0, 1, 3), Ok(true));
+/// assert_eq!(uf.union_with(1, 2, 4), Ok(true));
+/// assert_eq!(uf.diff(0, 2), Some(7));
+/// assert_eq!(uf.diff(2, 0), Some(-7));
+/// assert_eq!(uf.diff(0, 3), None);
+///
+/// // Consistent constraints are accepted, contradicting ones are rejected.
+/// assert_eq!(uf.union_with(0, 2, 7), Ok(false));
+/// let err = uf.union_with(0, 2, 5).unwrap_err();
+/// assert_eq!((err.expected, err.actual), (5, 7));
+/// ```
+#[derive(Debug)]
+pub struct WeightedUf<G> {
+    link_parent: Vec<usize>,
+    weight: Vec<G>,
+    size: Vec<usize>,
+    set_count: usize,
+}
+
+impl<G: Clone> Clone for WeightedUf<G> {
+    #[inline]
+    fn clone(&self) -> WeightedUf<G> {
+        WeightedUf {
+            link_parent: self.link_parent.clone(),
+            weight: self.weight.clone(),
+            size: self.size.clone(),
+            set_count: self.set_count,
+        }
+    }
+
+    #[inline]
+    fn clone_from(&mut self, other: &WeightedUf<G>) {
+        self.link_parent.clone_from(&other.link_parent);
+        self.weight.clone_from(&other.weight);
+        self.size.clone_from(&other.size);
+        self.set_count = other.set_count;
+    }
+}
+
+impl<G: Group> WeightedUf<G> {
+    /// Creates `WeightedUf` struct that contains `len` sets.
+    #[inline]
+    pub fn new(len: usize) -> WeightedUf<G> {
+        WeightedUf {
+            link_parent: (0..len).collect(),
+            weight: vec![G::identity(); len],
+            size: vec![1; len],
+            set_count: len,
+        }
+    }
+
+    /// Returns the size of `self`.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.link_parent.len()
+    }
+
+    /// Returns the number of disjoint sets in `self`.
+    #[inline]
+    pub fn set_count(&self) -> usize {
+        self.set_count
+    }
+
+    /// Inserts a new set into the union-find and returns its key.
+    #[inline]
+    pub fn insert(&mut self) -> usize {
+        let key = self.link_parent.len();
+        self.link_parent.push(key);
+        self.weight.push(G::identity());
+        self.size.push(1);
+        self.set_count += 1;
+        key
+    }
+
+    /// Adds the constraint `potential(key0) - potential(key1) = diff`,
+    /// joining the sets that contain given keys.
+    ///
+    /// Returns `Ok(true)` if these keys were belonged to different sets, and
+    /// `Ok(false)` if they were already in the same set and the constraint
+    /// agrees with the existing ones. Returns `Err` without modifying the
+    /// sets if the constraint contradicts the existing ones.
+    #[inline]
+    pub fn union_with(&mut self, key0: usize, key1: usize, diff: G) -> Result<bool, Conflict<G>> {
+        let (k0, w0) = self.find_with_potential(key0);
+        let (k1, w1) = self.find_with_potential(key1);
+        if k0 == k1 {
+            let actual = w0.combine(w1.inverse());
+            if actual != diff {
+                return Err(Conflict {
+                    expected: diff,
+                    actual,
+                });
+            }
+            return Ok(false);
+        }
+
+        // potential(k1) - potential(k0) = w0 - w1 - diff
+        let w = w0.combine(w1.inverse()).combine(diff.inverse());
+        let (parent, child, w) = if self.size[k0] >= self.size[k1] {
+            (k0, k1, w)
+        } else {
+            (k1, k0, w.inverse())
+        };
+        self.link_parent[child] = parent;
+        self.weight[child] = w;
+        self.size[parent] += self.size[child];
+
+        self.set_count -= 1;
+
+        Ok(true)
+    }
+
+    /// Returns the identifier of the set that the key belongs to.
+    #[inline]
+    pub fn find(&mut self, key: usize) -> usize {
+        self.find_with_potential(key).0
+    }
+
+    /// Returns `true` if given keys belong to the same set.
+    #[inline]
+    pub fn connected(&mut self, key0: usize, key1: usize) -> bool {
+        self.find(key0) == self.find(key1)
+    }
+
+    /// Returns `potential(key0) - potential(key1)`, or `None` if given keys
+    /// belong to different sets.
+    #[inline]
+    pub fn diff(&mut self, key0: usize, key1: usize) -> Option<G> {
+        let (k0, w0) = self.find_with_potential(key0);
+        let (k1, w1) = self.find_with_potential(key1);
+        if k0 != k1 {
+            return None;
+        }
+        Some(w0.combine(w1.inverse()))
+    }
+
+    /// Returns the root of the set that the key belongs to and the
+    /// potential of the key relative to the root.
+    #[inline]
+    fn find_with_potential(&mut self, key: usize) -> (usize, G) {
+        // `w` is the potential of `key` relative to `k`.
+        let mut k = key;
+        let mut w = G::identity();
+        loop {
+            let p = self.link_parent[k];
+            if p == k {
+                return (k, w);
+            }
+            let pp = self.link_parent[p];
+            if pp != p {
+                self.weight[k] = self.weight[k].clone().combine(self.weight[p].clone());
+                self.link_parent[k] = pp;
+            }
+            w = w.combine(self.weight[k].clone());
+            k = pp;
+        }
+    }
+}
+
+/// Union-Find implementation that checks whether the graph made of the added
+/// edges is bipartite.
+///
+/// Each set is a connected component of the graph, and the parity of a key
+/// tells which side of the component it is on.
+///
+/// ```
+/// use union_find::{BipartiteUf, Parity};
+///
+/// let mut uf = BipartiteUf::new(4);
+/// assert_eq!(uf.add_edge(0, 1), Ok(true));
+/// assert_eq!(uf.add_edge(1, 2), Ok(true));
+/// assert_eq!(uf.same_side(0, 2), Some(true));
+/// assert_eq!(uf.same_side(0, 1), Some(false));
+/// assert_eq!(uf.same_side(0, 3), None);
+///
+/// // 0 - 1 - 2 - 0 would be an odd cycle.
+/// let err = uf.add_edge(2, 0).unwrap_err();
+/// assert_eq!(err.actual, Parity::Even);
+/// ```
+pub type BipartiteUf = WeightedUf<Parity>;
+
+impl WeightedUf<Parity> {
+    /// Adds an edge between given keys, which puts them on different sides.
+    ///
+    /// Returns `Ok(true)` if these keys were belonged to different sets, and
+    /// `Ok(false)` if they were already on different sides of the same set.
+    /// Returns `Err` without modifying the sets if they were on the same
+    /// side, i.e. the edge would close an odd cycle.
+    #[inline]
+    pub fn add_edge(&mut self, key0: usize, key1: usize) -> Result<bool, Conflict<Parity>> {
+        self.union_with(key0, key1, Parity::Odd)
+    }
+
+    /// Returns whether given keys are on the same side, or `None` if they
+    /// belong to different sets.
+    #[inline]
+    pub fn same_side(&mut self, key0: usize, key1: usize) -> Option<bool> {
+        self.diff(key0, key1).map(|p| p == Parity::Even)
+    }
+}
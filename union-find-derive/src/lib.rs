@@ -0,0 +1,332 @@
+// Copyright 2016 union-find-rs Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Derive macros for the `Union`, `Detach` and `Split` traits of the
+//! `union-find` crate.
+//!
+//! This crate is re-exported by `union-find` with the `derive` feature, and
+//! should not be used directly.
+
+#![warn(missing_docs)]
+#![warn(unused_qualifications)]
+#![warn(unused_results)]
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Ident, Index, Member, Path, Result, Type};
+
+/// Derives `Union` for a struct whose fields are merged one by one.
+///
+/// Exactly one field must be marked with `#[union(link)]`. Its type
+/// implements `Union` and decides how the sets are linked. Every other field
+/// is merged by `Merge::merge`, or by the function given with
+/// `#[union(with = path)]`, which takes the values of the left and the right
+/// sets and returns the merged value.
+#[proc_macro_derive(Union, attributes(union))]
+pub fn derive_union(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_union(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `Detach` for a struct that derives `Union`.
+///
+/// The link field is detached by `Detach::detach`, and every other field by
+/// `Unmerge::unmerge`, or by the function given with
+/// `#[union(detach = path)]`, which takes the value of the set and a
+/// reference to the value of the removed element. A field merged with
+/// `#[union(with = path)]` must also have `detach = path`, and a field whose
+/// type does not implement `Unmerge`, such as `Min`, fails to compile, so
+/// that no stale data is kept in the sets.
+#[proc_macro_derive(Detach, attributes(union))]
+pub fn derive_detach(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_detach(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `Split` for a struct that derives `Union`.
+///
+/// The link field is split by `Split::split`, and every other field is
+/// recomputed for each part by merging the values of its elements, in the
+/// same way as `Union`.
+#[proc_macro_derive(Split, attributes(union))]
+pub fn derive_split(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_split(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// How a field is merged.
+enum Strategy {
+    Link,
+    Merge,
+    With(Path),
+}
+
+/// A field of the struct and its `union` attributes.
+struct Field<'a> {
+    member: Member,
+    var: Ident,
+    ty: &'a Type,
+    strategy: Strategy,
+    detach: Option<Path>,
+}
+
+fn parse_field(i: usize, field: &syn::Field) -> Result<Field<'_>> {
+    let mut link = false;
+    let mut with = None;
+    let mut detach = None;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("union")) {
+        attr.parse_nested_meta(|meta| {
+            let duplicate = if meta.path.is_ident("link") {
+                std::mem::replace(&mut link, true)
+            } else if meta.path.is_ident("with") {
+                with.replace(meta.value()?.parse()?).is_some()
+            } else if meta.path.is_ident("detach") {
+                detach.replace(meta.value()?.parse()?).is_some()
+            } else {
+                return Err(meta.error("expected `link`, `with = path` or `detach = path`"));
+            };
+            if duplicate {
+                return Err(meta.error("duplicate `union` attribute"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let strategy = match (link, with) {
+        (true, Some(_)) => {
+            return Err(Error::new_spanned(
+                field,
+                "`link` cannot be combined with `with = path`",
+            ))
+        }
+        (true, None) if detach.is_some() => {
+            return Err(Error::new_spanned(
+                field,
+                "`link` cannot be combined with `detach = path`",
+            ))
+        }
+        (true, None) => Strategy::Link,
+        (false, Some(path)) => Strategy::With(path),
+        (false, None) => Strategy::Merge,
+    };
+    let member = match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(i)),
+    };
+    Ok(Field {
+        member,
+        var: format_ident!("field{}", i),
+        ty: &field.ty,
+        strategy,
+        detach,
+    })
+}
+
+/// Returns the fields of the struct, checking that exactly one of them is
+/// the link field.
+fn parse_fields<'a>(input: &'a DeriveInput, name: &str) -> Result<Vec<Field<'a>>> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new_spanned(
+                input,
+                format!("`{}` can only be derived for structs", name),
+            ))
+        }
+    };
+
+    let parsed = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| parse_field(i, field))
+        .collect::<Result<Vec<_>>>()?;
+    let mut links = fields
+        .iter()
+        .zip(&parsed)
+        .filter(|(_, field)| matches!(field.strategy, Strategy::Link));
+    if links.next().is_none() {
+        return Err(Error::new(
+            Span::call_site(),
+            "a field must be marked with `#[union(link)]`",
+        ));
+    }
+    if let Some((field, _)) = links.next() {
+        return Err(Error::new_spanned(
+            field,
+            "only one field can be marked with `#[union(link)]`",
+        ));
+    }
+    Ok(parsed)
+}
+
+fn expand_union(input: &DeriveInput) -> Result<TokenStream> {
+    let fields = parse_fields(input, "Union")?;
+
+    let mut merges = vec![];
+    let mut link_var = None;
+    for Field {
+        member,
+        var,
+        strategy,
+        ..
+    } in &fields
+    {
+        match strategy {
+            Strategy::Link => {
+                merges.push(quote! {
+                    let link = ::union_find::Union::union(lval.#member, rval.#member);
+                });
+                link_var = Some(var);
+            }
+            Strategy::Merge => merges.push(quote! {
+                let #var = ::union_find::Merge::merge(lval.#member, rval.#member);
+            }),
+            Strategy::With(path) => merges.push(quote! {
+                let #var = #path(lval.#member, rval.#member);
+            }),
+        }
+    }
+    let link_var = link_var.unwrap();
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let inits = fields
+        .iter()
+        .map(|Field { member, var, .. }| quote!(#member: #var));
+    let body = quote!(#name { #(#inits),* });
+
+    Ok(quote! {
+        impl #impl_generics ::union_find::Union for #name #ty_generics #where_clause {
+            #[inline]
+            fn union(lval: Self, rval: Self) -> ::union_find::UnionResult<Self> {
+                #(#merges)*
+                match link {
+                    ::union_find::UnionResult::Left(#link_var) => {
+                        ::union_find::UnionResult::Left(#body)
+                    }
+                    ::union_find::UnionResult::Right(#link_var) => {
+                        ::union_find::UnionResult::Right(#body)
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn expand_detach(input: &DeriveInput) -> Result<TokenStream> {
+    let fields = parse_fields(input, "Detach")?;
+
+    let mut inits = vec![];
+    for field in &fields {
+        let Field {
+            member, ty, detach, ..
+        } = field;
+        let value =
+            match (&field.strategy, detach) {
+                (_, Some(path)) => quote!(#path(set.#member, &removed.#member)),
+                (Strategy::Link, None) => quote_spanned! {ty.span()=>
+                    ::union_find::Detach::detach(set.#member, &removed.#member)
+                },
+                (Strategy::Merge, None) => quote_spanned! {ty.span()=>
+                    ::union_find::Unmerge::unmerge(set.#member, &removed.#member)
+                },
+                (Strategy::With(path), None) => return Err(Error::new_spanned(
+                    path,
+                    "a field merged with `with = path` needs `detach = path` to derive `Detach`",
+                )),
+            };
+        inits.push(quote!(#member: #value));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::union_find::Detach for #name #ty_generics #where_clause {
+            #[inline]
+            fn detach(set: Self, removed: &Self) -> Self {
+                #name { #(#inits),* }
+            }
+        }
+    })
+}
+
+fn expand_split(input: &DeriveInput) -> Result<TokenStream> {
+    let fields = parse_fields(input, "Split")?;
+
+    let mut unzips = vec![];
+    let mut pushes = vec![];
+    let mut splits = vec![];
+    for field in &fields {
+        let Field {
+            member, var, ty, ..
+        } = field;
+        let (var0, var1) = (format_ident!("{}_0", var), format_ident!("{}_1", var));
+        unzips.push(quote! {
+            let mut #var0 = ::std::vec::Vec::with_capacity(part0.len());
+            let mut #var1 = ::std::vec::Vec::with_capacity(part1.len());
+        });
+        pushes.push((
+            quote!(#var0.push(value.#member);),
+            quote!(#var1.push(value.#member);),
+        ));
+        let merge = match &field.strategy {
+            Strategy::Link => {
+                splits.push(quote_spanned! {ty.span()=>
+                    let (#var0, #var1) = ::union_find::Split::split(set.#member, #var0, #var1);
+                });
+                continue;
+            }
+            Strategy::Merge => quote!(::union_find::Merge::merge),
+            Strategy::With(path) => quote!(#path),
+        };
+        splits.push(quote! {
+            let #var0 = ::std::iter::Iterator::reduce(#var0.into_iter(), #merge).unwrap();
+            let #var1 = ::std::iter::Iterator::reduce(#var1.into_iter(), #merge).unwrap();
+        });
+    }
+    let (pushes0, pushes1): (Vec<_>, Vec<_>) = pushes.into_iter().unzip();
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let inits0 = fields.iter().map(|Field { member, var, .. }| {
+        let var0 = format_ident!("{}_0", var);
+        quote!(#member: #var0)
+    });
+    let inits1 = fields.iter().map(|Field { member, var, .. }| {
+        let var1 = format_ident!("{}_1", var);
+        quote!(#member: #var1)
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::union_find::Split for #name #ty_generics #where_clause {
+            #[inline]
+            fn split(
+                set: Self,
+                part0: ::std::vec::Vec<Self>,
+                part1: ::std::vec::Vec<Self>,
+            ) -> (Self, Self) {
+                #(#unzips)*
+                for value in part0 {
+                    #(#pushes0)*
+                }
+                for value in part1 {
+                    #(#pushes1)*
+                }
+                #(#splits)*
+                (#name { #(#inits0),* }, #name { #(#inits1),* })
+            }
+        }
+    })
+}